@@ -2,8 +2,10 @@ import maplibregl from "maplibre-gl";
 import { layers, namedFlavor } from "@protomaps/basemaps";
 import init, {
   find_properties,
+  geometry,
   get_landslide_data_wasm,
   initialize,
+  tolerance_for_zoom,
 } from "reversejp-wasm";
 
 // Type definitions for the WASM module
@@ -104,6 +106,9 @@ function initMap(): maplibregl.Map {
 
       markers.push(marker);
 
+      // Highlight the most detailed region containing the point
+      highlightRegion(map, properties[properties.length - 1]?.code);
+
       // Display the information
       displayInfo(lng, lat, properties);
     } catch (error) {
@@ -115,6 +120,46 @@ function initMap(): maplibregl.Map {
   return map;
 }
 
+// Outline the region of `code`, or clear the highlight when undefined
+function highlightRegion(map: maplibregl.Map, code: string | undefined): void {
+  const sourceId = "highlight-source";
+  const region = code
+    ? geometry(code, tolerance_for_zoom(Math.round(map.getZoom())))
+    : undefined;
+  const data: GeoJSON.FeatureCollection = {
+    type: "FeatureCollection",
+    features: region
+      ? [{ type: "Feature", geometry: region, properties: { code } }]
+      : [],
+  };
+
+  const source = map.getSource(sourceId) as maplibregl.GeoJSONSource | undefined;
+  if (source) {
+    source.setData(data);
+    return;
+  }
+
+  map.addSource(sourceId, { type: "geojson", data });
+  map.addLayer({
+    id: "highlight-fill",
+    type: "fill",
+    source: sourceId,
+    paint: {
+      "fill-color": "#2563eb",
+      "fill-opacity": 0.25,
+    },
+  });
+  map.addLayer({
+    id: "highlight-outline",
+    type: "line",
+    source: sourceId,
+    paint: {
+      "line-color": "#2563eb",
+      "line-width": 2,
+    },
+  });
+}
+
 // Load and display landslide GeoJSON data
 function loadLandslideData(
   map: maplibregl.Map,
@@ -1,7 +1,18 @@
-from typing import List
+from os import PathLike
+from typing import Any, Dict, List, Optional, Sequence, Tuple, TypeVar, Union
+
+import numpy as np
+import numpy.typing as npt
+
+Frame = TypeVar("Frame")
 
 class Property:
-    """A class representing a property (region)."""
+    """
+    A region, as returned by lookups.
+
+    Properties are immutable values: they compare equal and hash alike when
+    all their fields are equal, and can be pickled.
+    """
 
     code: str
     """The region code."""
@@ -12,7 +23,197 @@ class Property:
     en_name: str
     """The region name in English."""
 
-    def __init__(self, code: str, name: str, en_name: str) -> None: ...
+    kana: Optional[str]
+    """The reading of the name in hiragana, if known."""
+
+    def __init__(
+        self, code: str, name: str, en_name: str = "", kana: Optional[str] = None
+    ) -> None: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def to_dict(self) -> Dict[str, Optional[str]]:
+        """Return the fields as a dict with `code`, `name`, `en_name` and `kana` keys."""
+        ...
+
+    @property
+    def level(self) -> Optional[str]:
+        """
+        The level of the region in the JMA area hierarchy.
+
+        One of `center`, `office`, `class10`, `class15` or `class20` for regions
+        of `area.json`, otherwise `prefecture`, `class10` or `class20` inferred
+        from the format of the code, or None.
+        """
+        ...
+
+    @property
+    def parent(self) -> Optional["Property"]:
+        """The parent region in `area.json`, or None for centers and unknown codes."""
+        ...
+
+    def ancestors(self) -> List["Property"]:
+        """The ancestors of the region in `area.json`, from the parent up to the center."""
+        ...
+
+    def children(self) -> List["Property"]:
+        """The children of the region in `area.json`, e.g. the class20s of a class15."""
+        ...
+
+    @property
+    def point(self) -> Optional[Tuple[float, float]]:
+        """
+        The representative point of the region in `xy.json`, as (longitude, latitude).
+
+        Only centers, offices and class20s have a point.
+        """
+        ...
+
+class PropertyArray:
+    """
+    Region properties of many points, exchanged through the Arrow PyCapsule interface.
+
+    A nullable struct<code, name, en_name> array of large strings, null where no
+    region contains the point. Pass it to `pyarrow.array`, `polars.Series`,
+    or query it with DuckDB.
+    """
+
+    def __len__(self) -> int: ...
+    def __arrow_c_schema__(self) -> object: ...
+    def __arrow_c_array__(
+        self, requested_schema: Optional[object] = None
+    ) -> Tuple[object, object]: ...
+    def __arrow_c_stream__(self, requested_schema: Optional[object] = None) -> object: ...
+
+class ReverseJp:
+    """A reverse geocoder over the embedded regions and/or custom GeoJSON regions."""
+
+    def __init__(self, embedded: bool = True) -> None:
+        """
+        Create a reverse geocoder.
+
+        Args:
+            embedded: Load the embedded regions, otherwise create an empty instance
+
+        Raises:
+            RuntimeError: If the embedded data cannot be loaded
+        """
+        ...
+
+    @staticmethod
+    def from_file(
+        path: Union[str, PathLike[str]], embedded: bool = False
+    ) -> "ReverseJp":
+        """
+        Create a reverse geocoder from a GeoJSON FeatureCollection file.
+
+        Features must have MultiPolygon geometries and `code` and `name` properties.
+
+        Args:
+            path: Path of the GeoJSON file
+            embedded: Also load the embedded regions
+
+        Raises:
+            OSError: If the file cannot be read
+            ValueError: If the file is not a valid FeatureCollection
+        """
+        ...
+
+    @staticmethod
+    def from_geojson(geojson: str, embedded: bool = False) -> "ReverseJp":
+        """
+        Create a reverse geocoder from a GeoJSON FeatureCollection string.
+
+        Args:
+            geojson: The GeoJSON FeatureCollection
+            embedded: Also load the embedded regions
+
+        Raises:
+            ValueError: If the string is not a valid FeatureCollection
+        """
+        ...
+
+    def find_properties(self, longitude: float, latitude: float) -> List[Property]:
+        """
+        Find all properties (regions) that contain the specified longitude/latitude coordinate.
+
+        Args:
+            longitude: The longitude coordinate
+            latitude: The latitude coordinate
+
+        Returns:
+            A list of Property objects representing regions that contain the specified point
+        """
+        ...
+
+    def find_codes(
+        self,
+        longitudes: Union[npt.ArrayLike, Sequence[float]],
+        latitudes: Union[npt.ArrayLike, Sequence[float]],
+        threads: int = 1,
+    ) -> Dict[str, np.ndarray]:
+        """
+        Find the region codes of many points at once. Requires NumPy.
+
+        See `reversejp.find_codes`.
+        """
+        ...
+
+    def find_properties_arrow(
+        self,
+        longitudes: object,
+        latitudes: object,
+        level: str = "class20",
+        threads: int = 1,
+    ) -> PropertyArray:
+        """
+        Find the region of one level containing each point, as an Arrow array.
+
+        See `reversejp.find_properties_arrow`.
+        """
+        ...
+
+    def add_columns(
+        self,
+        df: Frame,
+        longitude: Optional[str] = None,
+        latitude: Optional[str] = None,
+        threads: int = 1,
+    ) -> Frame:
+        """
+        Return a copy of a DataFrame with the code and name of each level.
+
+        See `reversejp.add_columns`.
+        """
+        ...
+
+    def regions(self, level: Optional[str] = None) -> Dict[str, List[Any]]:
+        """
+        Every loaded region as columns of attributes and WKB geometries.
+
+        See `reversejp.regions`.
+        """
+        ...
+
+    def geometry(self, code: str) -> Optional[Dict[str, Any]]:
+        """
+        The geometry of a region as a GeoJSON MultiPolygon mapping.
+
+        See `reversejp.geometry`.
+        """
+        ...
+
+    def to_geojson(self, level: Optional[str] = None) -> Dict[str, Any]:
+        """
+        The loaded regions as a GeoJSON FeatureCollection mapping.
+
+        See `reversejp.to_geojson`.
+        """
+        ...
+
+    @property
+    def __geo_interface__(self) -> Dict[str, Any]:
+        """Every loaded region as a GeoJSON FeatureCollection mapping."""
+        ...
 
 def find_properties(longitude: float, latitude: float) -> List[Property]:
     """
@@ -22,8 +223,207 @@ def find_properties(longitude: float, latitude: float) -> List[Property]:
         longitude: The longitude coordinate
         latitude: The latitude coordinate
 
+    The embedded data is loaded on the first call and shared by later calls.
+
     Returns:
         A list of Property objects representing regions that contain the specified point
+
+    Raises:
+        RuntimeError: If the embedded data cannot be loaded
+    """
+    ...
+
+def find_codes(
+    longitudes: Union[npt.ArrayLike, Sequence[float]],
+    latitudes: Union[npt.ArrayLike, Sequence[float]],
+    threads: int = 1,
+) -> Dict[str, np.ndarray]:
+    """
+    Find the region codes of many points at once. Requires NumPy.
+
+    The lookups run in Rust without holding the GIL, and without creating a
+    Property object per point.
+
+    Args:
+        longitudes: 1-D array of longitudes, converted to float64
+        latitudes: 1-D array of latitudes, of the same length
+        threads: Number of threads to use, 0 for one per CPU
+
+    Returns:
+        A dict of object arrays of codes, one per level, with None where no
+        region contains the point. `class10` and `class20` are always present;
+        `prefecture` and `other` only when such codes are found.
+
+    Raises:
+        ValueError: If the arrays are not 1-D or differ in length
+    """
+    ...
+
+def find_properties_arrow(
+    longitudes: object,
+    latitudes: object,
+    level: str = "class20",
+    threads: int = 1,
+) -> PropertyArray:
+    """
+    Find the region of one level containing each point, as an Arrow array.
+
+    Coordinates are read through the Arrow PyCapsule interface, without creating
+    Python objects per point. Null coordinates match no region.
+
+    Args:
+        longitudes: A float32 or float64 Arrow array or stream, e.g. a pyarrow
+            Array or ChunkedArray, or a Polars Series
+        latitudes: Latitudes of the same length
+        level: `prefecture`, `class10`, `class20`, or `other` for codes of
+            none of the levels. Any value of `Property.level` is accepted,
+            levels not told apart by the code format only give nulls
+        threads: Number of threads to use, 0 for one per CPU
+
+    Returns:
+        A PropertyArray with the code, name and en_name of each region
+
+    Raises:
+        TypeError: If the coordinates do not implement the Arrow PyCapsule interface
+        ValueError: If the coordinates are not float arrays, differ in length,
+            or the level is unknown
+    """
+    ...
+
+def add_columns(
+    df: Frame,
+    longitude: Optional[str] = None,
+    latitude: Optional[str] = None,
+    threads: int = 1,
+) -> Frame:
+    """
+    Return a copy of a pandas DataFrame with the code and name of each level.
+
+    Adds `class10_code`, `class10_name`, `class20_code` and `class20_name`
+    columns, None where no region contains the point. Requires NumPy.
+
+    Args:
+        df: A DataFrame with longitude and latitude columns, or a GeoDataFrame
+            of points when the column names are omitted
+        longitude: Name of the longitude column
+        latitude: Name of the latitude column
+        threads: Number of threads to use, 0 for one per CPU
+
+    Returns:
+        A copy of the frame with the new columns, built with `DataFrame.assign`
+
+    Raises:
+        ValueError: If only one column name is given, or neither is given and
+            the frame has no geometry
+    """
+    ...
+
+def regions(level: Optional[str] = None) -> Dict[str, List[Any]]:
+    """
+    Every loaded region as columns of attributes and WKB geometries.
+
+    Each region appears once, with all its polygons merged into a MultiPolygon.
+    The result can be passed to `pandas.DataFrame`, or to `geopandas.GeoDataFrame`
+    after decoding the geometries with `geopandas.GeoSeries.from_wkb`.
+
+    Args:
+        level: Only return regions of this level, e.g. `class10` or `class20`
+
+    Returns:
+        A dict with `code`, `name`, `en_name` and `geometry` (WKB bytes) lists
+    """
+    ...
+
+def geometry(code: str) -> Optional[Dict[str, Any]]:
+    """
+    The geometry of a region as a GeoJSON MultiPolygon mapping.
+
+    All polygons of the region are merged, holes included. The mapping can be
+    passed to `shapely.geometry.shape`.
+
+    Args:
+        code: The region code, e.g. `130010` or `1310100`
+
+    Returns:
+        A dict with `type` and `coordinates`, or None if the code is not loaded
+    """
+    ...
+
+def to_geojson(level: Optional[str] = None) -> Dict[str, Any]:
+    """
+    The loaded regions as a GeoJSON FeatureCollection mapping.
+
+    Each region appears once, with the fields of its Property as properties.
+    The mapping can be passed to `geopandas.GeoDataFrame.from_features`.
+
+    Args:
+        level: Only return regions of this level, e.g. `class10` or `class20`
+
+    Returns:
+        A FeatureCollection dict
+    """
+    ...
+
+def get_area(code: str, level: Optional[str] = None) -> Optional[Property]:
+    """
+    Find an area of the JMA area hierarchy (`area.json`) by code.
+
+    Use `Property.parent`, `Property.ancestors` and `Property.children` to
+    navigate the hierarchy from the area.
+
+    Args:
+        code: The area code
+        level: The level of the area, one of `prefecture`, `center`, `office`,
+            `class10`, `class15` or `class20`, as given by `Property.level`.
+            As codes are reused across levels, class20s are searched first
+            when omitted, then class10s, offices, class15s and centers.
+            Prefectures are only found with their level.
+
+    Returns:
+        The area, or None if the code is not found
+
+    Raises:
+        ValueError: If the level is unknown
+    """
+    ...
+
+def representative_point(
+    code: str, level: Optional[str] = None
+) -> Optional[Tuple[float, float]]:
+    """
+    The representative point of an area in `xy.json`, as (longitude, latitude).
+
+    Args:
+        code: The area code
+        level: The level of the area, as given by `Property.level`. Only
+            centers, offices and class20s have points. Class20s are searched
+            first when omitted.
+
+    Returns:
+        The point, or None if the area has none
+
+    Raises:
+        ValueError: If the level is unknown
+    """
+    ...
+
+def search(query: str, mode: str = "prefix") -> List[Property]:
+    """
+    Search the areas of `area.json` by Japanese name, English name or reading.
+
+    Queries may be in kanji, kana (full or half-width) or romaji, e.g. `渋谷`,
+    `しぶや`, `ｼﾌﾞﾔ`, `shibuya` or `sibuya`.
+
+    Args:
+        query: The text to search
+        mode: `exact` for whole names, `prefix` for names starting with the
+            query, `fuzzy` to also allow one typo every four characters
+
+    Returns:
+        The matching areas, best matches first
+
+    Raises:
+        ValueError: If the mode is unknown
     """
     ...
 
@@ -0,0 +1,165 @@
+//! Area hierarchy, representative points and name search over the embedded JMA data.
+
+use ::reversejp::area::AreaData;
+use ::reversejp::search::{SearchIndex, SearchMode};
+use ::reversejp::xy::XyData;
+use ::reversejp::{AreaCode, AreaLevel, prefecture};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+
+use crate::property::Property;
+
+// Each dataset is loaded on first use
+static AREA_DATA: PyOnceLock<AreaData> = PyOnceLock::new();
+static XY_DATA: PyOnceLock<XyData> = PyOnceLock::new();
+static SEARCH_INDEX: PyOnceLock<SearchIndex> = PyOnceLock::new();
+
+fn load_error(what: &str, err: Box<dyn std::error::Error>) -> PyErr {
+    PyRuntimeError::new_err(format!("Failed to load embedded {}: {}", what, err))
+}
+
+pub fn get_area_data(py: Python<'_>) -> PyResult<&'static AreaData> {
+    AREA_DATA.get_or_try_init(py, || {
+        AreaData::with_embedded_data().map_err(|err| load_error("area data", err))
+    })
+}
+
+pub fn get_xy_data(py: Python<'_>) -> PyResult<&'static XyData> {
+    XY_DATA.get_or_try_init(py, || {
+        XyData::with_embedded_data().map_err(|err| load_error("representative points", err))
+    })
+}
+
+fn get_search_index(py: Python<'_>) -> PyResult<&'static SearchIndex> {
+    let area = get_area_data(py)?;
+    SEARCH_INDEX.get_or_try_init(py, || Ok(SearchIndex::new(area.clone())))
+}
+
+// Every level, from the top of the hierarchy
+const LEVELS: [AreaLevel; 6] = [
+    AreaLevel::Prefecture,
+    AreaLevel::Center,
+    AreaLevel::Office,
+    AreaLevel::Class10,
+    AreaLevel::Class15,
+    AreaLevel::Class20,
+];
+
+/// Name of the level of codes in none of the levels, e.g. custom regions
+pub const OTHER_LEVEL: &str = "other";
+
+/// Name of a level, as used by `area.json` and the `level` arguments
+pub fn level_str(level: AreaLevel) -> &'static str {
+    match level {
+        AreaLevel::Prefecture => "prefecture",
+        AreaLevel::Center => "center",
+        AreaLevel::Office => "office",
+        AreaLevel::Class10 => "class10",
+        AreaLevel::Class15 => "class15",
+        AreaLevel::Class20 => "class20",
+    }
+}
+
+fn unknown_level(level: &str, extra: Option<&'static str>) -> PyErr {
+    let names: Vec<&str> = LEVELS.into_iter().map(level_str).chain(extra).collect();
+    PyValueError::new_err(format!(
+        "Unknown level: {}, expected one of {}",
+        level,
+        names.join(", ")
+    ))
+}
+
+/// Parse the name of a level, see `level_str`
+pub fn parse_level(level: &str) -> PyResult<AreaLevel> {
+    LEVELS
+        .into_iter()
+        .find(|&known| level_str(known) == level)
+        .ok_or_else(|| unknown_level(level, None))
+}
+
+/// Name of the level of a code from its format, see `AreaCode::level`
+pub fn code_level_str(code: &AreaCode) -> &'static str {
+    code.level().map_or(OTHER_LEVEL, level_str)
+}
+
+/// Parse a level name as given by `code_level_str`, `other` giving None
+pub fn parse_code_level(level: &str) -> PyResult<Option<AreaLevel>> {
+    if level == OTHER_LEVEL {
+        return Ok(None);
+    }
+    parse_level(level)
+        .map(Some)
+        .map_err(|_| unknown_level(level, Some(OTHER_LEVEL)))
+}
+
+/// The level of the children of an area
+pub fn child_level(level: AreaLevel) -> Option<AreaLevel> {
+    match level {
+        AreaLevel::Center => Some(AreaLevel::Office),
+        AreaLevel::Office => Some(AreaLevel::Class10),
+        AreaLevel::Class10 => Some(AreaLevel::Class15),
+        AreaLevel::Class15 => Some(AreaLevel::Class20),
+        AreaLevel::Class20 | AreaLevel::Prefecture => None,
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (code, level = None))]
+pub fn get_area(py: Python<'_>, code: &str, level: Option<&str>) -> PyResult<Option<Property>> {
+    let area = get_area_data(py)?;
+    let entry = match level.map(parse_level).transpose()? {
+        // Prefectures are not part of area.json
+        Some(AreaLevel::Prefecture) => {
+            return Ok(prefecture::prefecture(code)
+                .filter(|prefecture| prefecture.code == code)
+                .map(|prefecture| {
+                    Property::new(
+                        prefecture.code.to_string(),
+                        prefecture.name.to_string(),
+                        prefecture.en_name.to_string(),
+                        None,
+                    )
+                }));
+        }
+        Some(level) => area.areas(level).and_then(|areas| areas.get(code)),
+        None => area.find(code).map(|(_, entry)| entry),
+    };
+    Ok(entry.map(|entry| Property::from(entry.properties(code))))
+}
+
+#[pyfunction]
+#[pyo3(signature = (code, level = None))]
+pub fn representative_point(
+    py: Python<'_>,
+    code: &str,
+    level: Option<&str>,
+) -> PyResult<Option<(f64, f64)>> {
+    let xy = get_xy_data(py)?;
+    Ok(match level {
+        Some(level) => xy.get(parse_level(level)?, code),
+        None => xy.point(code),
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (query, mode = "prefix"))]
+pub fn search(py: Python<'_>, query: &str, mode: &str) -> PyResult<Vec<Property>> {
+    let mode = match mode {
+        "exact" => SearchMode::Exact,
+        "prefix" => SearchMode::Prefix,
+        "fuzzy" => SearchMode::Fuzzy,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "Unknown search mode: {}, expected one of exact, prefix, fuzzy",
+                mode
+            )));
+        }
+    };
+    let index = get_search_index(py)?;
+    Ok(py
+        .detach(|| index.search(query, mode))
+        .into_iter()
+        .map(|result| Property::new(result.code.into(), result.name, result.en_name, result.kana))
+        .collect())
+}
@@ -0,0 +1,521 @@
+//! A minimal implementation of the Arrow C data interface.
+//!
+//! Coordinates are read from, and region properties returned as, any object
+//! implementing the Arrow PyCapsule interface (pyarrow, Polars, DuckDB, ...),
+//! without depending on an Arrow implementation. Only the few types needed
+//! are supported: float32/float64 arrays in, a struct of large strings out.
+
+use std::ffi::{CString, c_char, c_int, c_void};
+use std::ptr;
+use std::sync::Arc;
+
+use ::reversejp::Properties;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyCapsule, PyTuple};
+
+const ARROW_FLAG_NULLABLE: i64 = 2;
+
+#[repr(C)]
+pub struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+pub struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+pub struct ArrowArrayStream {
+    get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> c_int>,
+    get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+// Owned structures are released when dropped, unless moved out by a consumer
+impl Drop for ArrowSchema {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl Drop for ArrowArray {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl Drop for ArrowArrayStream {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+impl ArrowSchema {
+    fn empty() -> Self {
+        ArrowSchema {
+            format: ptr::null(),
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+}
+
+impl ArrowArray {
+    fn empty() -> Self {
+        ArrowArray {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: ptr::null_mut(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+}
+
+// Capsules may be destroyed on any thread; the exported data is owned and immutable
+#[repr(transparent)]
+struct Exported<T>(T);
+
+unsafe impl<T> Send for Exported<T> {}
+
+// Children pointers, null when there are none
+fn children_ptr<T>(children: &mut [*mut T]) -> *mut *mut T {
+    if children.is_empty() {
+        ptr::null_mut()
+    } else {
+        children.as_mut_ptr()
+    }
+}
+
+struct SchemaData {
+    format: CString,
+    name: CString,
+    children: Vec<*mut ArrowSchema>,
+}
+
+fn export_schema(format: &str, name: &str, children: Vec<ArrowSchema>) -> ArrowSchema {
+    let mut data = Box::new(SchemaData {
+        format: CString::new(format).unwrap(),
+        name: CString::new(name).unwrap(),
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    ArrowSchema {
+        format: data.format.as_ptr(),
+        name: data.name.as_ptr(),
+        metadata: ptr::null(),
+        flags: ARROW_FLAG_NULLABLE,
+        n_children: data.children.len() as i64,
+        children: children_ptr(&mut data.children),
+        dictionary: ptr::null_mut(),
+        release: Some(release_schema),
+        private_data: Box::into_raw(data).cast(),
+    }
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    unsafe {
+        let data = Box::from_raw((*schema).private_data.cast::<SchemaData>());
+        for &child in &data.children {
+            drop(Box::from_raw(child));
+        }
+        (*schema).release = None;
+    }
+}
+
+enum Buffer {
+    Bytes(Vec<u8>),
+    Int64(Vec<i64>),
+}
+
+impl Buffer {
+    fn as_ptr(&self) -> *const c_void {
+        match self {
+            Buffer::Bytes(bytes) => bytes.as_ptr().cast(),
+            Buffer::Int64(values) => values.as_ptr().cast(),
+        }
+    }
+}
+
+struct ArrayData {
+    _buffers: Vec<Option<Buffer>>,
+    buffer_ptrs: Vec<*const c_void>,
+    children: Vec<*mut ArrowArray>,
+}
+
+fn export_array(
+    length: usize,
+    null_count: usize,
+    buffers: Vec<Option<Buffer>>,
+    children: Vec<ArrowArray>,
+) -> ArrowArray {
+    let mut data = Box::new(ArrayData {
+        buffer_ptrs: buffers
+            .iter()
+            .map(|buffer| buffer.as_ref().map_or(ptr::null(), Buffer::as_ptr))
+            .collect(),
+        _buffers: buffers,
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    ArrowArray {
+        length: length as i64,
+        null_count: null_count as i64,
+        offset: 0,
+        n_buffers: data.buffer_ptrs.len() as i64,
+        n_children: data.children.len() as i64,
+        buffers: data.buffer_ptrs.as_mut_ptr(),
+        children: children_ptr(&mut data.children),
+        dictionary: ptr::null_mut(),
+        release: Some(release_array),
+        private_data: Box::into_raw(data).cast(),
+    }
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    unsafe {
+        let data = Box::from_raw((*array).private_data.cast::<ArrayData>());
+        for &child in &data.children {
+            drop(Box::from_raw(child));
+        }
+        (*array).release = None;
+    }
+}
+
+// Validity bitmap and null count, without a bitmap when every value is valid
+fn validity(valid: &[bool]) -> (Option<Buffer>, usize) {
+    let null_count = valid.iter().filter(|valid| !**valid).count();
+    if null_count == 0 {
+        return (None, 0);
+    }
+    let mut bits = vec![0u8; valid.len().div_ceil(8)];
+    for (i, _) in valid.iter().enumerate().filter(|(_, valid)| **valid) {
+        bits[i / 8] |= 1 << (i % 8);
+    }
+    (Some(Buffer::Bytes(bits)), null_count)
+}
+
+fn large_string_array<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrowArray {
+    let mut offsets: Vec<i64> = vec![0];
+    let mut data: Vec<u8> = Vec::new();
+    let mut valid: Vec<bool> = Vec::new();
+    for value in values {
+        if let Some(value) = value {
+            data.extend_from_slice(value.as_bytes());
+        }
+        offsets.push(data.len() as i64);
+        valid.push(value.is_some());
+    }
+    let (validity, null_count) = validity(&valid);
+    export_array(
+        valid.len(),
+        null_count,
+        vec![
+            validity,
+            Some(Buffer::Int64(offsets)),
+            Some(Buffer::Bytes(data)),
+        ],
+        vec![],
+    )
+}
+
+// struct<code: large_utf8, name: large_utf8, en_name: large_utf8>
+fn properties_schema() -> ArrowSchema {
+    export_schema(
+        "+s",
+        "",
+        ["code", "name", "en_name"]
+            .into_iter()
+            .map(|name| export_schema("U", name, vec![]))
+            .collect(),
+    )
+}
+
+fn properties_array(properties: &[Option<Properties>]) -> ArrowArray {
+    let codes = large_string_array(
+        properties
+            .iter()
+            .map(|p| p.as_ref().map(|p| p.code.as_str())),
+    );
+    let names = large_string_array(
+        properties
+            .iter()
+            .map(|p| p.as_ref().map(|p| p.name.as_str())),
+    );
+    let en_names = large_string_array(
+        properties
+            .iter()
+            .map(|p| p.as_ref().map(|p| p.en_name.as_str())),
+    );
+    let valid: Vec<bool> = properties.iter().map(Option::is_some).collect();
+    let (validity, null_count) = validity(&valid);
+    export_array(
+        properties.len(),
+        null_count,
+        vec![validity],
+        vec![codes, names, en_names],
+    )
+}
+
+struct StreamData {
+    properties: Arc<Vec<Option<Properties>>>,
+    done: bool,
+}
+
+unsafe extern "C" fn stream_get_schema(
+    _stream: *mut ArrowArrayStream,
+    out: *mut ArrowSchema,
+) -> c_int {
+    unsafe { ptr::write(out, properties_schema()) };
+    0
+}
+
+// The whole array is returned as a single chunk
+unsafe extern "C" fn stream_get_next(stream: *mut ArrowArrayStream, out: *mut ArrowArray) -> c_int {
+    unsafe {
+        let data = &mut *(*stream).private_data.cast::<StreamData>();
+        if data.done {
+            ptr::write(out, ArrowArray::empty());
+        } else {
+            data.done = true;
+            ptr::write(out, properties_array(&data.properties));
+        }
+    }
+    0
+}
+
+unsafe extern "C" fn stream_get_last_error(_stream: *mut ArrowArrayStream) -> *const c_char {
+    ptr::null()
+}
+
+unsafe extern "C" fn stream_release(stream: *mut ArrowArrayStream) {
+    unsafe {
+        drop(Box::from_raw((*stream).private_data.cast::<StreamData>()));
+        (*stream).release = None;
+    }
+}
+
+/// Region properties of many points, exported through the Arrow PyCapsule interface
+///
+/// A nullable `struct<code, name, en_name>` of large strings, null where no
+/// region contains the point.
+#[pyclass(frozen)]
+pub struct PropertyArray {
+    properties: Arc<Vec<Option<Properties>>>,
+}
+
+impl PropertyArray {
+    pub fn new(properties: Vec<Option<Properties>>) -> Self {
+        PropertyArray {
+            properties: Arc::new(properties),
+        }
+    }
+}
+
+#[pymethods]
+impl PropertyArray {
+    fn __len__(&self) -> usize {
+        self.properties.len()
+    }
+
+    fn __arrow_c_schema__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyCapsule>> {
+        PyCapsule::new_with_value(py, Exported(properties_schema()), c"arrow_schema")
+    }
+
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyTuple>> {
+        // Only one schema is supported, consumers cast if they need another
+        let _ = requested_schema;
+        let schema = self.__arrow_c_schema__(py)?;
+        let array = PyCapsule::new_with_value(
+            py,
+            Exported(properties_array(&self.properties)),
+            c"arrow_array",
+        )?;
+        PyTuple::new(py, [schema, array])
+    }
+
+    #[pyo3(signature = (requested_schema = None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let _ = requested_schema;
+        let data = Box::new(StreamData {
+            properties: Arc::clone(&self.properties),
+            done: false,
+        });
+        let stream = ArrowArrayStream {
+            get_schema: Some(stream_get_schema),
+            get_next: Some(stream_get_next),
+            get_last_error: Some(stream_get_last_error),
+            release: Some(stream_release),
+            private_data: Box::into_raw(data).cast(),
+        };
+        PyCapsule::new_with_value(py, Exported(stream), c"arrow_array_stream")
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Float {
+    Float32,
+    Float64,
+}
+
+fn float_format(schema: &ArrowSchema) -> PyResult<Float> {
+    let format = if schema.format.is_null() {
+        ""
+    } else {
+        unsafe { std::ffi::CStr::from_ptr(schema.format) }
+            .to_str()
+            .unwrap_or("")
+    };
+    match format {
+        "f" => Ok(Float::Float32),
+        "g" => Ok(Float::Float64),
+        _ => Err(PyValueError::new_err(format!(
+            "Coordinates must be float32 or float64 arrays, got Arrow format '{}'",
+            format
+        ))),
+    }
+}
+
+// Append the values of a float array, nulls as NaN
+unsafe fn read_floats(float: Float, array: &ArrowArray, values: &mut Vec<f64>) -> PyResult<()> {
+    if array.n_buffers != 2 {
+        return Err(PyValueError::new_err("Malformed Arrow float array"));
+    }
+    unsafe {
+        let buffers = std::slice::from_raw_parts(array.buffers, 2);
+        let validity = buffers[0].cast::<u8>();
+        let offset = array.offset as usize;
+        for i in offset..offset + array.length as usize {
+            let valid = validity.is_null() || (*validity.add(i / 8) >> (i % 8)) & 1 == 1;
+            let value = match float {
+                Float::Float32 => f64::from(*buffers[1].cast::<f32>().add(i)),
+                Float::Float64 => *buffers[1].cast::<f64>().add(i),
+            };
+            values.push(if valid { value } else { f64::NAN });
+        }
+    }
+    Ok(())
+}
+
+fn stream_error(stream: &mut ArrowArrayStream, code: c_int) -> PyErr {
+    let message = stream
+        .get_last_error
+        .map(|get_last_error| unsafe { get_last_error(stream) })
+        .filter(|message| !message.is_null())
+        .map(|message| {
+            unsafe { std::ffi::CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned()
+        })
+        .unwrap_or_else(|| format!("error code {}", code));
+    PyRuntimeError::new_err(format!("Failed to read Arrow stream: {}", message))
+}
+
+fn read_stream(stream: &mut ArrowArrayStream) -> PyResult<Vec<f64>> {
+    let (Some(get_schema), Some(get_next)) = (stream.get_schema, stream.get_next) else {
+        return Err(PyValueError::new_err("Released Arrow stream"));
+    };
+    let mut schema = ArrowSchema::empty();
+    match unsafe { get_schema(stream, &mut schema) } {
+        0 => {}
+        code => return Err(stream_error(stream, code)),
+    }
+    let float = float_format(&schema)?;
+
+    let mut values = Vec::new();
+    loop {
+        let mut array = ArrowArray::empty();
+        match unsafe { get_next(stream, &mut array) } {
+            0 => {}
+            code => return Err(stream_error(stream, code)),
+        }
+        if array.release.is_none() {
+            return Ok(values);
+        }
+        unsafe { read_floats(float, &array, &mut values)? };
+    }
+}
+
+/// Read coordinates from an Arrow array or stream, e.g. a pyarrow array or Polars Series
+///
+/// Nulls are read as NaN, which no region contains.
+pub fn read_coordinates(values: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    if values.hasattr("__arrow_c_stream__")? {
+        let capsule = values.call_method0("__arrow_c_stream__")?;
+        let stream = capsule
+            .cast::<PyCapsule>()?
+            .pointer_checked(Some(c"arrow_array_stream"))?
+            .cast::<ArrowArrayStream>();
+        // The stream stays in the capsule, which releases it
+        read_stream(unsafe { &mut *stream.as_ptr() })
+    } else if values.hasattr("__arrow_c_array__")? {
+        let (schema, array): (Bound<'_, PyCapsule>, Bound<'_, PyCapsule>) =
+            values.call_method0("__arrow_c_array__")?.extract()?;
+        let schema = schema
+            .pointer_checked(Some(c"arrow_schema"))?
+            .cast::<ArrowSchema>();
+        let array = array
+            .pointer_checked(Some(c"arrow_array"))?
+            .cast::<ArrowArray>();
+        let mut values = Vec::new();
+        unsafe {
+            read_floats(float_format(schema.as_ref())?, array.as_ref(), &mut values)?;
+        }
+        Ok(values)
+    } else {
+        Err(PyTypeError::new_err(
+            "Expected an object implementing the Arrow PyCapsule interface",
+        ))
+    }
+}
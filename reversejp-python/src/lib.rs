@@ -1,53 +1,499 @@
-use ::reversejp::{Properties, ReverseJp};
+mod area;
+mod arrow;
+mod property;
+
+use ::reversejp::{Geometry, Properties, ReverseJp as RustReverseJp, wkb};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use std::sync::OnceLock;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{PyBytes, PyDict};
+use std::error::Error;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::thread;
+
+use arrow::PropertyArray;
+use property::Property;
+
+// Global instance of ReverseJp used by the module-level functions, created on first use
+static GLOBAL_INSTANCE: PyOnceLock<RustReverseJp> = PyOnceLock::new();
 
-// Global instance of ReverseJp that's created once on first access
-static GLOBAL_INSTANCE: OnceLock<ReverseJp> = OnceLock::new();
+fn get_global_instance(py: Python<'_>) -> PyResult<&'static RustReverseJp> {
+    // Parse without holding the GIL, other threads wait on the lock instead
+    GLOBAL_INSTANCE.get_or_try_init(py, || py.detach(load_embedded_data))
+}
 
-fn get_global_instance() -> &'static ReverseJp {
-    GLOBAL_INSTANCE.get_or_init(|| match ReverseJp::with_embedded_data() {
-        Ok(instance) => instance,
-        Err(_) => panic!("Failed to initialize ReverseJp with embedded data"),
+fn load_embedded_data() -> PyResult<RustReverseJp> {
+    RustReverseJp::with_embedded_data().map_err(|err| {
+        PyRuntimeError::new_err(format!(
+            "Failed to initialize ReverseJp with embedded data: {}",
+            err
+        ))
     })
 }
 
-// #[derive(IntoPyObject, IntoPyObjectRef)]
+// I/O errors become OSError (e.g. FileNotFoundError), anything else ValueError
+fn to_py_err(err: Box<dyn Error>) -> PyErr {
+    match err.downcast::<std::io::Error>() {
+        Ok(err) => PyErr::from(*err),
+        Err(err) => PyValueError::new_err(err.to_string()),
+    }
+}
+
+fn to_py_properties(properties: Vec<Properties>) -> Vec<Property> {
+    properties.into_iter().map(Property::from).collect()
+}
+
+// Properties of the regions containing each point
+fn lookup_properties(
+    reverse_jp: &RustReverseJp,
+    longitudes: &[f64],
+    latitudes: &[f64],
+    threads: usize,
+) -> Vec<Vec<Properties>> {
+    let lookup =
+        |(longitude, latitude): (&f64, &f64)| reverse_jp.find_properties(*longitude, *latitude);
+    if threads <= 1 || longitudes.len() < 2 {
+        return longitudes.iter().zip(latitudes).map(lookup).collect();
+    }
+
+    let chunk_size = longitudes.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = longitudes
+            .chunks(chunk_size)
+            .zip(latitudes.chunks(chunk_size))
+            .map(|(longitudes, latitudes)| {
+                scope.spawn(move || {
+                    longitudes
+                        .iter()
+                        .zip(latitudes)
+                        .map(lookup)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+            })
+            .collect()
+    })
+}
 
-#[pyclass]
-struct Property {
-    #[pyo3(get)]
-    code: String,
-    #[pyo3(get)]
-    name: String,
-    #[pyo3(get)]
-    en_name: String,
+// Convert a sequence of numbers to a contiguous float64 buffer through NumPy
+fn to_f64_vec(numpy: &Bound<'_, PyModule>, values: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    let array = numpy.call_method1("ascontiguousarray", (values, "float64"))?;
+    if array.getattr("ndim")?.extract::<usize>()? != 1 {
+        return Err(PyValueError::new_err("Coordinates must be 1-D arrays"));
+    }
+    let bytes = array.call_method0("tobytes")?;
+    Ok(bytes
+        .cast::<PyBytes>()?
+        .as_bytes()
+        .chunks_exact(8)
+        .map(|chunk| f64::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect())
 }
 
-impl From<Properties> for Property {
-    fn from(props: Properties) -> Self {
-        Self {
-            code: props.code,
-            name: props.name,
-            en_name: props.en_name,
+type LevelColumns = Vec<(&'static str, Vec<Option<Properties>>)>;
+
+// Look up many points, keeping the first region of each level per point
+//
+// class10 and class20 columns are always returned, other levels when found.
+fn lookup_levels(
+    py: Python<'_>,
+    reverse_jp: &RustReverseJp,
+    longitudes: Vec<f64>,
+    latitudes: Vec<f64>,
+    threads: usize,
+) -> PyResult<LevelColumns> {
+    if longitudes.len() != latitudes.len() {
+        return Err(PyValueError::new_err(format!(
+            "Longitudes and latitudes differ in length: {} != {}",
+            longitudes.len(),
+            latitudes.len()
+        )));
+    }
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        threads => threads,
+    };
+
+    let rows = py.detach(|| lookup_properties(reverse_jp, &longitudes, &latitudes, threads));
+
+    let len = rows.len();
+    let mut columns: LevelColumns =
+        vec![("class10", vec![None; len]), ("class20", vec![None; len])];
+    for (i, row) in rows.into_iter().enumerate() {
+        for props in row {
+            let level = area::code_level_str(&props.code);
+            let column = match columns.iter().position(|(name, _)| *name == level) {
+                Some(column) => column,
+                None => {
+                    columns.push((level, vec![None; len]));
+                    columns.len() - 1
+                }
+            };
+            columns[column].1[i].get_or_insert(props);
         }
     }
+    Ok(columns)
 }
 
-#[pyfunction]
-fn find_properties(_py: Python, longitude: f64, latitude: f64) -> PyResult<Vec<Property>> {
-    let reverse_jp = get_global_instance();
-    let rust_properties = reverse_jp.find_properties(longitude, latitude);
+// An object array of one field of each region, None where there is no region
+fn object_array<'py>(
+    numpy: &Bound<'py, PyModule>,
+    column: &[Option<Properties>],
+    field: impl Fn(&Properties) -> &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let values: Vec<Option<&str>> = column.iter().map(|p| p.as_ref().map(&field)).collect();
+    numpy.call_method1("array", (values, "object"))
+}
+
+fn find_codes_with<'py>(
+    py: Python<'py>,
+    reverse_jp: &RustReverseJp,
+    longitudes: &Bound<'py, PyAny>,
+    latitudes: &Bound<'py, PyAny>,
+    threads: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let numpy = py.import("numpy")?;
+    let longitudes = to_f64_vec(&numpy, longitudes)?;
+    let latitudes = to_f64_vec(&numpy, latitudes)?;
+    let columns = lookup_levels(py, reverse_jp, longitudes, latitudes, threads)?;
+
+    let result = PyDict::new(py);
+    for (level, column) in columns {
+        result.set_item(level, object_array(&numpy, &column, |p| &p.code)?)?;
+    }
+    Ok(result)
+}
+
+fn add_columns_with<'py>(
+    py: Python<'py>,
+    reverse_jp: &RustReverseJp,
+    df: &Bound<'py, PyAny>,
+    longitude: Option<&str>,
+    latitude: Option<&str>,
+    threads: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (longitudes, latitudes) = match (longitude, latitude) {
+        (Some(longitude), Some(latitude)) => (df.get_item(longitude)?, df.get_item(latitude)?),
+        (None, None) if df.hasattr("geometry")? => {
+            let geometry = df.getattr("geometry")?;
+            (geometry.getattr("x")?, geometry.getattr("y")?)
+        }
+        _ => {
+            return Err(PyValueError::new_err(
+                "Both longitude and latitude columns are required, unless the frame has point geometries",
+            ));
+        }
+    };
+    let longitudes = longitudes.call_method0("to_numpy")?;
+    let latitudes = latitudes.call_method0("to_numpy")?;
+
+    let numpy = py.import("numpy")?;
+    let longitudes = to_f64_vec(&numpy, &longitudes)?;
+    let latitudes = to_f64_vec(&numpy, &latitudes)?;
+    let columns = lookup_levels(py, reverse_jp, longitudes, latitudes, threads)?;
+
+    let new_columns = PyDict::new(py);
+    for (level, column) in columns {
+        new_columns.set_item(
+            format!("{}_code", level),
+            object_array(&numpy, &column, |p| &p.code)?,
+        )?;
+        new_columns.set_item(
+            format!("{}_name", level),
+            object_array(&numpy, &column, |p| &p.name)?,
+        )?;
+    }
+    df.call_method("assign", (), Some(&new_columns))
+}
+
+fn find_properties_arrow_with(
+    py: Python<'_>,
+    reverse_jp: &RustReverseJp,
+    longitudes: &Bound<'_, PyAny>,
+    latitudes: &Bound<'_, PyAny>,
+    level: &str,
+    threads: usize,
+) -> PyResult<PropertyArray> {
+    // Every level name is accepted, levels not told by the code format, e.g.
+    // offices, only give nulls
+    area::parse_code_level(level)?;
+    let longitudes = arrow::read_coordinates(longitudes)?;
+    let latitudes = arrow::read_coordinates(latitudes)?;
+    let len = longitudes.len();
+    let column = lookup_levels(py, reverse_jp, longitudes, latitudes, threads)?
+        .into_iter()
+        .find(|(name, _)| *name == level)
+        .map_or_else(|| vec![None; len], |(_, column)| column);
+    Ok(PropertyArray::new(column))
+}
+
+fn regions_with<'py>(
+    py: Python<'py>,
+    reverse_jp: &RustReverseJp,
+    level: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let features = reverse_jp
+        .feature_collection(|props| {
+            level.is_none_or(|level| area::code_level_str(&props.code) == level)
+        })
+        .features;
 
-    let mut result = Vec::new();
-    for props in rust_properties {
-        let py_property = Property::from(props);
-        result.push(py_property);
+    let mut codes = Vec::with_capacity(features.len());
+    let mut names = Vec::with_capacity(features.len());
+    let mut en_names = Vec::with_capacity(features.len());
+    let mut geometries = Vec::with_capacity(features.len());
+    for feature in features {
+        geometries.push(PyBytes::new(py, &wkb::to_wkb(&feature.geometry)));
+        codes.push(String::from(feature.properties.code));
+        names.push(feature.properties.name);
+        en_names.push(feature.properties.en_name);
     }
 
+    let result = PyDict::new(py);
+    result.set_item("code", codes)?;
+    result.set_item("name", names)?;
+    result.set_item("en_name", en_names)?;
+    result.set_item("geometry", geometries)?;
+    Ok(result)
+}
+
+// A GeoJSON geometry mapping, accepted by e.g. `shapely.geometry.shape`
+fn geometry_dict<'py>(py: Python<'py>, geometry: &Geometry) -> PyResult<Bound<'py, PyDict>> {
+    let result = PyDict::new(py);
+    result.set_item("type", &geometry.geometry_type)?;
+    result.set_item("coordinates", &geometry.coordinates)?;
+    Ok(result)
+}
+
+fn geometry_with<'py>(
+    py: Python<'py>,
+    reverse_jp: &RustReverseJp,
+    code: &str,
+) -> PyResult<Option<Bound<'py, PyDict>>> {
+    reverse_jp
+        .geometry(code)
+        .map(|geometry| geometry_dict(py, &geometry))
+        .transpose()
+}
+
+fn to_geojson_with<'py>(
+    py: Python<'py>,
+    reverse_jp: &RustReverseJp,
+    level: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let features = reverse_jp
+        .feature_collection(|props| {
+            level.is_none_or(|level| area::code_level_str(&props.code) == level)
+        })
+        .features
+        .into_iter()
+        .map(|feature| {
+            let result = PyDict::new(py);
+            result.set_item("type", "Feature")?;
+            result.set_item("geometry", geometry_dict(py, &feature.geometry)?)?;
+            result.set_item(
+                "properties",
+                Property::from(feature.properties).to_dict(py)?,
+            )?;
+            Ok(result)
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    let result = PyDict::new(py);
+    result.set_item("type", "FeatureCollection")?;
+    result.set_item("features", features)?;
     Ok(result)
 }
 
+/// Reverse geocoder over the embedded regions and/or custom GeoJSON regions
+#[pyclass(name = "ReverseJp", frozen)]
+struct PyReverseJp {
+    inner: RustReverseJp,
+}
+
+impl PyReverseJp {
+    fn with_base(py: Python<'_>, embedded: bool) -> PyResult<RustReverseJp> {
+        if embedded {
+            py.detach(load_embedded_data)
+        } else {
+            Ok(RustReverseJp::new())
+        }
+    }
+}
+
+#[pymethods]
+impl PyReverseJp {
+    /// Create an instance with the embedded data, or an empty one
+    #[new]
+    #[pyo3(signature = (embedded = true))]
+    fn new(py: Python<'_>, embedded: bool) -> PyResult<Self> {
+        Ok(Self {
+            inner: Self::with_base(py, embedded)?,
+        })
+    }
+
+    /// Create an instance from a GeoJSON `FeatureCollection` file
+    #[staticmethod]
+    #[pyo3(signature = (path, embedded = false))]
+    fn from_file(py: Python<'_>, path: PathBuf, embedded: bool) -> PyResult<Self> {
+        let mut inner = Self::with_base(py, embedded)?;
+        py.detach(|| inner.load_file(path).map_err(to_py_err))?;
+        Ok(Self { inner })
+    }
+
+    /// Create an instance from a GeoJSON `FeatureCollection` string
+    #[staticmethod]
+    #[pyo3(signature = (geojson, embedded = false))]
+    fn from_geojson(py: Python<'_>, geojson: &str, embedded: bool) -> PyResult<Self> {
+        let mut inner = Self::with_base(py, embedded)?;
+        py.detach(|| inner.load_geojson(geojson).map_err(to_py_err))?;
+        Ok(Self { inner })
+    }
+
+    fn find_properties(&self, py: Python<'_>, longitude: f64, latitude: f64) -> Vec<Property> {
+        to_py_properties(py.detach(|| self.inner.find_properties(longitude, latitude)))
+    }
+
+    /// Find the region codes of many points, one NumPy array per level
+    #[pyo3(signature = (longitudes, latitudes, threads = 1))]
+    fn find_codes<'py>(
+        &self,
+        py: Python<'py>,
+        longitudes: &Bound<'py, PyAny>,
+        latitudes: &Bound<'py, PyAny>,
+        threads: usize,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        find_codes_with(py, &self.inner, longitudes, latitudes, threads)
+    }
+
+    /// Return a copy of a DataFrame with the code and name of each level
+    #[pyo3(signature = (df, longitude = None, latitude = None, threads = 1))]
+    fn add_columns<'py>(
+        &self,
+        py: Python<'py>,
+        df: &Bound<'py, PyAny>,
+        longitude: Option<&str>,
+        latitude: Option<&str>,
+        threads: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        add_columns_with(py, &self.inner, df, longitude, latitude, threads)
+    }
+
+    /// Find the region of one level containing each point, as an Arrow array
+    #[pyo3(signature = (longitudes, latitudes, level = "class20", threads = 1))]
+    fn find_properties_arrow(
+        &self,
+        py: Python<'_>,
+        longitudes: &Bound<'_, PyAny>,
+        latitudes: &Bound<'_, PyAny>,
+        level: &str,
+        threads: usize,
+    ) -> PyResult<PropertyArray> {
+        find_properties_arrow_with(py, &self.inner, longitudes, latitudes, level, threads)
+    }
+
+    /// The geometry of a region as a GeoJSON mapping
+    fn geometry<'py>(&self, py: Python<'py>, code: &str) -> PyResult<Option<Bound<'py, PyDict>>> {
+        geometry_with(py, &self.inner, code)
+    }
+
+    /// The loaded regions as a GeoJSON FeatureCollection mapping
+    #[pyo3(signature = (level = None))]
+    fn to_geojson<'py>(
+        &self,
+        py: Python<'py>,
+        level: Option<&str>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        to_geojson_with(py, &self.inner, level)
+    }
+
+    /// Every loaded region as a GeoJSON FeatureCollection
+    #[getter]
+    fn __geo_interface__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        to_geojson_with(py, &self.inner, None)
+    }
+
+    /// Every loaded region as columns of attributes and WKB geometries
+    #[pyo3(signature = (level = None))]
+    fn regions<'py>(&self, py: Python<'py>, level: Option<&str>) -> PyResult<Bound<'py, PyDict>> {
+        regions_with(py, &self.inner, level)
+    }
+}
+
+#[pyfunction]
+fn find_properties(py: Python, longitude: f64, latitude: f64) -> PyResult<Vec<Property>> {
+    let reverse_jp = get_global_instance(py)?;
+    Ok(to_py_properties(py.detach(|| {
+        reverse_jp.find_properties(longitude, latitude)
+    })))
+}
+
+#[pyfunction]
+#[pyo3(signature = (longitudes, latitudes, threads = 1))]
+fn find_codes<'py>(
+    py: Python<'py>,
+    longitudes: &Bound<'py, PyAny>,
+    latitudes: &Bound<'py, PyAny>,
+    threads: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let reverse_jp = get_global_instance(py)?;
+    find_codes_with(py, reverse_jp, longitudes, latitudes, threads)
+}
+
+#[pyfunction]
+#[pyo3(signature = (df, longitude = None, latitude = None, threads = 1))]
+fn add_columns<'py>(
+    py: Python<'py>,
+    df: &Bound<'py, PyAny>,
+    longitude: Option<&str>,
+    latitude: Option<&str>,
+    threads: usize,
+) -> PyResult<Bound<'py, PyAny>> {
+    let reverse_jp = get_global_instance(py)?;
+    add_columns_with(py, reverse_jp, df, longitude, latitude, threads)
+}
+
+#[pyfunction]
+#[pyo3(signature = (longitudes, latitudes, level = "class20", threads = 1))]
+fn find_properties_arrow(
+    py: Python<'_>,
+    longitudes: &Bound<'_, PyAny>,
+    latitudes: &Bound<'_, PyAny>,
+    level: &str,
+    threads: usize,
+) -> PyResult<PropertyArray> {
+    let reverse_jp = get_global_instance(py)?;
+    find_properties_arrow_with(py, reverse_jp, longitudes, latitudes, level, threads)
+}
+
+#[pyfunction]
+fn geometry<'py>(py: Python<'py>, code: &str) -> PyResult<Option<Bound<'py, PyDict>>> {
+    let reverse_jp = get_global_instance(py)?;
+    geometry_with(py, reverse_jp, code)
+}
+
+#[pyfunction]
+#[pyo3(signature = (level = None))]
+fn to_geojson<'py>(py: Python<'py>, level: Option<&str>) -> PyResult<Bound<'py, PyDict>> {
+    let reverse_jp = get_global_instance(py)?;
+    to_geojson_with(py, reverse_jp, level)
+}
+
+#[pyfunction]
+#[pyo3(signature = (level = None))]
+fn regions<'py>(py: Python<'py>, level: Option<&str>) -> PyResult<Bound<'py, PyDict>> {
+    let reverse_jp = get_global_instance(py)?;
+    regions_with(py, reverse_jp, level)
+}
+
 #[pyfunction]
 pub fn get_landslide_data(idx: usize) -> PyResult<String> {
     match ::reversejp::get_landslide_data(idx) {
@@ -59,13 +505,23 @@ pub fn get_landslide_data(idx: usize) -> PyResult<String> {
 }
 
 /// A Python module for reverse geocoding in Japan
-#[pymodule]
+///
+/// All state is immutable once loaded, so the module is safe to use without the GIL
+#[pymodule(gil_used = false)]
 fn reversejp(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
-    // Initialize the global instance upfront
-    let _ = get_global_instance();
-
     m.add_class::<Property>()?;
+    m.add_class::<PyReverseJp>()?;
+    m.add_class::<PropertyArray>()?;
     m.add_function(wrap_pyfunction!(find_properties, m)?)?;
+    m.add_function(wrap_pyfunction!(find_codes, m)?)?;
+    m.add_function(wrap_pyfunction!(find_properties_arrow, m)?)?;
+    m.add_function(wrap_pyfunction!(add_columns, m)?)?;
+    m.add_function(wrap_pyfunction!(regions, m)?)?;
+    m.add_function(wrap_pyfunction!(geometry, m)?)?;
+    m.add_function(wrap_pyfunction!(to_geojson, m)?)?;
+    m.add_function(wrap_pyfunction!(area::get_area, m)?)?;
+    m.add_function(wrap_pyfunction!(area::representative_point, m)?)?;
+    m.add_function(wrap_pyfunction!(area::search, m)?)?;
     m.add_function(wrap_pyfunction!(get_landslide_data, m)?)?;
 
     Ok(())
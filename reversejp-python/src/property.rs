@@ -0,0 +1,146 @@
+//! The `Property` value type returned by lookups.
+
+use ::reversejp::area::AreaData;
+use ::reversejp::{AreaCode, AreaLevel, Properties};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString, PyTuple};
+
+use crate::area::{child_level, get_area_data, get_xy_data, level_str};
+
+/// A region, compared and hashed by value
+#[pyclass(frozen, eq, hash, module = "reversejp", skip_from_py_object)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Property {
+    #[pyo3(get)]
+    code: String,
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    en_name: String,
+    #[pyo3(get)]
+    kana: Option<String>,
+}
+
+impl From<Properties> for Property {
+    fn from(props: Properties) -> Self {
+        Self {
+            code: props.code.into(),
+            name: props.name,
+            en_name: props.en_name,
+            kana: props.kana,
+        }
+    }
+}
+
+impl Property {
+    // The level of the code in `area.json`, or inferred from its format
+    fn area_level(&self, area: &AreaData) -> Option<AreaLevel> {
+        match area.find(&self.code) {
+            Some((level, _)) => Some(level),
+            None => AreaCode::from(self.code.as_str()).level(),
+        }
+    }
+}
+
+#[pymethods]
+impl Property {
+    #[new]
+    #[pyo3(signature = (code, name, en_name = String::new(), kana = None))]
+    pub fn new(code: String, name: String, en_name: String, kana: Option<String>) -> Self {
+        Self {
+            code,
+            name,
+            en_name,
+            kana,
+        }
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let repr =
+            |value: &str| -> PyResult<String> { Ok(PyString::new(py, value).repr()?.to_string()) };
+        let mut fields = format!(
+            "code={}, name={}, en_name={}",
+            repr(&self.code)?,
+            repr(&self.name)?,
+            repr(&self.en_name)?
+        );
+        if let Some(kana) = &self.kana {
+            fields.push_str(&format!(", kana={}", repr(kana)?));
+        }
+        Ok(format!("Property({})", fields))
+    }
+
+    fn __getnewargs__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        (
+            self.code.as_str(),
+            self.name.as_str(),
+            self.en_name.as_str(),
+            self.kana.as_deref(),
+        )
+            .into_pyobject(py)
+    }
+
+    /// The fields as a dict
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("code", &self.code)?;
+        dict.set_item("name", &self.name)?;
+        dict.set_item("en_name", &self.en_name)?;
+        dict.set_item("kana", &self.kana)?;
+        Ok(dict)
+    }
+
+    /// The level of the region, e.g. `class10` or `class20`
+    #[getter]
+    fn level(&self, py: Python<'_>) -> PyResult<Option<&'static str>> {
+        Ok(self.area_level(get_area_data(py)?).map(level_str))
+    }
+
+    /// The parent region in `area.json`
+    #[getter]
+    fn parent(&self, py: Python<'_>) -> PyResult<Option<Property>> {
+        Ok(self.ancestors(py)?.into_iter().next())
+    }
+
+    /// Children of the region in `area.json`
+    fn children(&self, py: Python<'_>) -> PyResult<Vec<Property>> {
+        let area = get_area_data(py)?;
+        let Some(level) = self.area_level(area) else {
+            return Ok(Vec::new());
+        };
+        let (Some(entry), Some(children)) = (
+            area.areas(level).and_then(|areas| areas.get(&self.code)),
+            child_level(level).and_then(|level| area.areas(level)),
+        ) else {
+            return Ok(Vec::new());
+        };
+        Ok(entry
+            .children
+            .iter()
+            .filter_map(|code| Some(Property::from(children.get(code)?.properties(code))))
+            .collect())
+    }
+
+    /// The representative point of the region in `xy.json`, as (longitude, latitude)
+    #[getter]
+    fn point(&self, py: Python<'_>) -> PyResult<Option<(f64, f64)>> {
+        let xy = get_xy_data(py)?;
+        Ok(match self.area_level(get_area_data(py)?) {
+            Some(level) => xy.get(level, &self.code),
+            None => xy.point(&self.code),
+        })
+    }
+
+    /// Ancestors of the region in `area.json`, from the parent up to the center
+    fn ancestors(&self, py: Python<'_>) -> PyResult<Vec<Property>> {
+        let area = get_area_data(py)?;
+        let Some(level) = self.area_level(area) else {
+            return Ok(Vec::new());
+        };
+        Ok(area
+            .ancestors(level, &self.code)
+            .into_iter()
+            .map(|(_, code, ancestor)| Property::from(ancestor.properties(code)))
+            .collect())
+    }
+}
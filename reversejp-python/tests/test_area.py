@@ -0,0 +1,84 @@
+from pytest import raises
+from reversejp import (
+    Property,
+    ReverseJp,
+    find_properties,
+    geometry,
+    get_area,
+    representative_point,
+    search,
+    to_geojson,
+)
+
+
+def test_geometry():
+    shibuya = geometry("1311300")
+    assert shibuya["type"] == "MultiPolygon"
+    ring = shibuya["coordinates"][0][0]
+    assert ring[0] == ring[-1]
+    assert all(len(point) == 2 for point in ring)
+    assert geometry("not-a-code") is None
+
+
+def test_to_geojson():
+    collection = to_geojson("class10")
+    assert collection["type"] == "FeatureCollection"
+    codes = [feature["properties"]["code"] for feature in collection["features"]]
+    assert "130010" in codes
+    assert len(codes) == len(set(codes))
+    assert all(len(code) == 6 for code in codes)
+
+    reverse_jp = ReverseJp.from_geojson(
+        """{"type": "FeatureCollection", "features": [{
+        "type": "Feature",
+        "geometry": {"type": "MultiPolygon",
+                     "coordinates": [[[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]]},
+        "properties": {"code": "custom", "name": "テスト"}}]}"""
+    )
+    feature = reverse_jp.__geo_interface__["features"][0]
+    assert feature["properties"]["code"] == "custom"
+    assert feature["geometry"]["coordinates"][0][0][2] == [10.0, 10.0]
+
+
+def test_hierarchy():
+    tokyo = get_area("130000")
+    assert tokyo.name == "東京都" and tokyo.level == "office"
+    assert "130010" in [child.code for child in tokyo.children()]
+    assert tokyo.parent.code == "010300"
+
+    region = get_area("130011", level="class15")
+    assert "1311300" in [child.code for child in region.children()]
+    assert get_area("1311300").children() == []
+    assert get_area("not-a-code") is None
+    with raises(ValueError):
+        get_area("130000", level="ward")
+
+
+def test_levels_round_trip():
+    # Every level reported by Property.level is accepted back
+    props = [Property("13", "東京都"), get_area("130000"), get_area("1311300")]
+    for prop in props:
+        assert get_area(prop.code, level=prop.level).code == prop.code
+    assert get_area("13", level="prefecture").en_name == "Tokyo"
+    assert get_area("130010", level="prefecture") is None
+    assert representative_point("13", "prefecture") is None
+    for level in ["center", "office", "class10", "class15", "class20"]:
+        assert get_area("not-a-code", level=level) is None
+
+
+def test_representative_point():
+    longitude, latitude = representative_point("1311300")
+    assert 139.6 < longitude < 139.8 and 35.6 < latitude < 35.7
+    assert "1311300" in [p.code for p in find_properties(longitude, latitude)]
+    assert representative_point("130000", "office") == get_area("130000").point
+    assert representative_point("130010") is None
+
+
+def test_search():
+    for query in ["渋谷区", "しぶや", "ｼﾌﾞﾔ", "shibuya", "Sibuya"]:
+        assert "1311300" in [p.code for p in search(query)], query
+    assert search("しぶや", "exact") == []
+    assert search("shibuyq", "fuzzy")[0].code == "1311300"
+    assert search("渋谷区", "exact")[0].kana == "しぶやく"
+    with raises(ValueError):
+        search("shibuya", "regex")
@@ -0,0 +1,136 @@
+import gc
+
+from pytest import importorskip, raises
+from reversejp import ReverseJp, find_properties_arrow
+
+pa = importorskip("pyarrow")
+
+LONGITUDES = [139.7673068, 135.5022535, None, 0.0]
+LATITUDES = [35.6809591, 34.6937378, 35.0, 0.0]
+
+
+def test_pyarrow_array():
+    result = find_properties_arrow(
+        pa.array(LONGITUDES, pa.float64()), pa.array(LATITUDES, pa.float64())
+    )
+    assert len(result) == 4
+    array = pa.array(result)
+    assert array.type == pa.struct(
+        [("code", pa.large_utf8()), ("name", pa.large_utf8()), ("en_name", pa.large_utf8())]
+    )
+    assert array.to_pylist() == [
+        {"code": "1310100", "name": "千代田区", "en_name": "Chiyoda City"},
+        {"code": "2710000", "name": "大阪市", "en_name": "Osaka City"},
+        None,
+        None,
+    ]
+
+
+def test_chunked_array_and_levels():
+    longitudes = pa.chunked_array([LONGITUDES[:1], LONGITUDES[1:]], pa.float32())
+    latitudes = pa.chunked_array([LATITUDES[:3], LATITUDES[3:]], pa.float64())
+    result = find_properties_arrow(longitudes, latitudes, level="class10", threads=2)
+    codes = pa.array(result).field("code").to_pylist()
+    assert codes == ["130010", "270000", None, None]
+
+    table = pa.table(result)
+    assert table.column_names == ["code", "name", "en_name"]
+    assert table.num_rows == 4
+
+
+def test_sliced_arrays_with_nulls():
+    # Offsets that do not start on a byte of the validity bitmap
+    longitudes = pa.array([None] * 9 + LONGITUDES, pa.float64()).slice(9)
+    latitudes = pa.array([1.0, None, 2.0, *LATITUDES, None], pa.float32())
+    latitudes = latitudes.slice(3, 4)
+    assert longitudes.offset == 9 and longitudes.null_count == 1
+    assert latitudes.offset == 3 and latitudes.null_count == 0
+    codes = pa.array(find_properties_arrow(longitudes, latitudes)).field("code")
+    assert codes.to_pylist() == ["1310100", "2710000", None, None]
+
+    # A null in the middle of a slice
+    latitudes = pa.array([None, 35.6809591, None, 34.6937378], pa.float64()).slice(1)
+    longitudes = pa.array([139.7673068, 139.7673068, 135.5022535], pa.float64())
+    codes = pa.array(find_properties_arrow(longitudes, latitudes)).field("code")
+    assert codes.to_pylist() == ["1310100", None, "2710000"]
+
+
+def test_multi_chunk_stream():
+    values = pa.array([None] + LONGITUDES * 2, pa.float64())
+    # Sliced chunks of uneven lengths, including an empty one
+    longitudes = pa.chunked_array(
+        [values.slice(1, 1), values.slice(2, 0), values.slice(2, 3), values.slice(5)]
+    )
+    latitudes = pa.chunked_array(
+        [LATITUDES[:3], LATITUDES[3:] + LATITUDES], pa.float64()
+    )
+    assert longitudes.num_chunks == 4
+    codes = pa.array(find_properties_arrow(longitudes, latitudes)).field("code")
+    assert codes.to_pylist() == ["1310100", "2710000", None, None] * 2
+
+
+def test_consumer_release():
+    result = find_properties_arrow(
+        pa.array(LONGITUDES, pa.float64()), pa.array(LATITUDES, pa.float64())
+    )
+    expected = pa.array(result).to_pylist()
+
+    # Imported data is owned by the consumer and outlives the result
+    array = pa.array(result)
+    table = pa.table(result)
+    del result
+    gc.collect()
+    assert array.to_pylist() == expected
+    codes = [row and row["code"] for row in expected]
+    assert table.column("code").to_pylist() == codes
+
+    # Every export is independent, and capsules dropped unconsumed release their data
+    result = find_properties_arrow(pa.array(LONGITUDES), pa.array(LATITUDES))
+    for _ in range(3):
+        result.__arrow_c_schema__()
+        result.__arrow_c_array__()
+        result.__arrow_c_stream__()
+    gc.collect()
+    assert pa.array(result).to_pylist() == expected
+    del array, table
+    gc.collect()
+    assert pa.table(result).num_rows == 4
+
+
+def test_custom_regions():
+    geojson = """{"type": "FeatureCollection", "features": [{
+        "type": "Feature",
+        "geometry": {"type": "MultiPolygon",
+                     "coordinates": [[[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]]},
+        "properties": {"code": "custom", "name": "テスト"}}]}"""
+    result = ReverseJp.from_geojson(geojson).find_properties_arrow(
+        pa.array([5.0]), pa.array([5.0]), level="other"
+    )
+    assert pa.array(result).to_pylist() == [
+        {"code": "custom", "name": "テスト", "en_name": ""}
+    ]
+
+
+def test_polars():
+    pl = importorskip("polars")
+    df = pl.DataFrame({"lng": LONGITUDES, "lat": LATITUDES})
+    regions = pl.Series("region", find_properties_arrow(df["lng"], df["lat"]))
+    df = df.with_columns(regions).unnest("region")
+    assert df["code"].to_list() == ["1310100", "2710000", None, None]
+    assert df["name"].to_list() == ["千代田区", "大阪市", None, None]
+
+
+def test_errors():
+    with raises(TypeError):
+        find_properties_arrow([139.7673068], [35.6809591])
+    with raises(ValueError):
+        find_properties_arrow(pa.array([1], pa.int64()), pa.array([1.0]))
+    with raises(ValueError):
+        find_properties_arrow(pa.array([1.0, 2.0]), pa.array([1.0]))
+    with raises(ValueError):
+        find_properties_arrow(pa.array([1.0]), pa.array([1.0]), level="ward")
+    # Levels of area.json are accepted, but codes never have them
+    result = find_properties_arrow(
+        pa.array([139.7673068]), pa.array([35.6809591]), level="office"
+    )
+    assert pa.array(result).to_pylist() == [None]
@@ -0,0 +1,52 @@
+from pytest import importorskip, raises
+from reversejp import ReverseJp, find_codes, find_properties
+
+np = importorskip("numpy")
+
+LONGITUDES = np.array([139.7673068, 139.701402, 135.5022535, 141.3469, 0.0])
+LATITUDES = np.array([35.6809591, 35.6585805, 34.6937378, 43.0619, 0.0])
+
+
+def test_find_codes():
+    codes = find_codes(LONGITUDES, LATITUDES)
+    assert set(codes) == {"class10", "class20"}
+    assert codes["class10"].tolist() == ["130010", "130010", "270000", "016010", None]
+    assert codes["class20"].tolist() == [
+        "1310100",
+        "1311300",
+        "2710000",
+        "0110000",
+        None,
+    ]
+
+
+def test_find_codes_matches_find_properties():
+    rng = np.random.default_rng(0)
+    longitudes = rng.uniform(129.0, 146.0, 500)
+    latitudes = rng.uniform(31.0, 45.0, 500)
+    codes = find_codes(longitudes, latitudes, threads=4)
+    for i, (lng, lat) in enumerate(zip(longitudes, latitudes)):
+        expected = {len(p.code): p.code for p in find_properties(lng, lat)}
+        assert codes["class10"][i] == expected.get(6)
+        assert codes["class20"][i] == expected.get(7)
+    assert find_codes(longitudes, latitudes, threads=0)["class20"].tolist() == (
+        codes["class20"].tolist()
+    )
+
+
+def test_find_codes_custom_levels():
+    geojson = """{"type": "FeatureCollection", "features": [{
+        "type": "Feature",
+        "geometry": {"type": "MultiPolygon",
+                     "coordinates": [[[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]]]},
+        "properties": {"code": "custom", "name": "テスト"}}]}"""
+    codes = ReverseJp.from_geojson(geojson).find_codes([5.0, 20.0], [5.0, 20.0])
+    assert codes["other"].tolist() == ["custom", None]
+    assert codes["class20"].tolist() == [None, None]
+
+
+def test_find_codes_errors():
+    with raises(ValueError):
+        find_codes(LONGITUDES, LATITUDES[:2])
+    with raises(ValueError):
+        find_codes(LONGITUDES.reshape(5, 1), LATITUDES.reshape(5, 1))
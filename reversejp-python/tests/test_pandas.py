@@ -0,0 +1,54 @@
+from pytest import importorskip, raises
+from reversejp import add_columns, regions
+
+pd = importorskip("pandas")
+
+
+def test_add_columns():
+    df = pd.DataFrame(
+        {"lng": [139.7673068, 135.5022535, 0.0], "lat": [35.6809591, 34.6937378, 0.0]},
+        index=[10, 20, 30],
+    )
+    result = add_columns(df, "lng", "lat")
+    assert list(df.columns) == ["lng", "lat"]
+    assert list(result.index) == [10, 20, 30]
+    assert result["class10_code"].tolist() == ["130010", "270000", None]
+    assert result["class10_name"].tolist() == ["東京都", "大阪府", None]
+    assert result["class20_code"].tolist() == ["1310100", "2710000", None]
+    assert result["class20_name"].tolist() == ["千代田区", "大阪市", None]
+
+    with raises(ValueError):
+        add_columns(df, "lng")
+    with raises(ValueError):
+        add_columns(df)
+
+
+def test_add_columns_geodataframe():
+    gpd = importorskip("geopandas")
+    gdf = gpd.GeoDataFrame(
+        geometry=gpd.points_from_xy([139.701402], [35.6585805]), crs="EPSG:4326"
+    )
+    result = add_columns(gdf)
+    assert result["class20_name"].tolist() == ["渋谷区"]
+    assert isinstance(result, gpd.GeoDataFrame)
+
+
+def test_regions():
+    data = regions()
+    assert len(data["code"]) == len(set(data["code"]))
+    assert {"130010", "1311300"} <= set(data["code"])
+
+    class20 = pd.DataFrame(regions("class20"))
+    assert (class20["code"].str.len() == 7).all()
+    assert class20.set_index("code").loc["1311300", "name"] == "渋谷区"
+    assert class20["geometry"].map(type).eq(bytes).all()
+
+
+def test_regions_geodataframe():
+    gpd = importorskip("geopandas")
+    data = regions("class20")
+    geometry = gpd.GeoSeries.from_wkb(data.pop("geometry"), crs="EPSG:4326")
+    gdf = gpd.GeoDataFrame(data, geometry=geometry)
+    shibuya = gdf[gdf["code"] == "1311300"].geometry.iloc[0]
+    assert shibuya.geom_type == "MultiPolygon"
+    assert shibuya.contains(gpd.points_from_xy([139.701402], [35.6585805])[0])
@@ -0,0 +1,55 @@
+import copy
+import pickle
+
+from reversejp import Property, find_properties
+
+
+def shibuya():
+    return find_properties(139.701402, 35.6585805)[1]
+
+
+def test_value_semantics():
+    prop = shibuya()
+    assert prop == Property("1311300", "渋谷区", "Shibuya City")
+    assert prop != Property("1311300", "渋谷区", "Shibuya")
+    assert prop == shibuya() and hash(prop) == hash(shibuya())
+    assert len({prop, shibuya(), Property("130010", "東京都", "Tokyo")}) == 2
+    assert repr(prop) == "Property(code='1311300', name='渋谷区', en_name='Shibuya City')"
+    assert prop.to_dict() == {
+        "code": "1311300",
+        "name": "渋谷区",
+        "en_name": "Shibuya City",
+        "kana": None,
+    }
+
+
+def test_pickle_and_copy():
+    prop = Property("0110000", "札幌市", "Sapporo City", "さっぽろし")
+    assert pickle.loads(pickle.dumps(prop)) == prop
+    assert copy.deepcopy(prop) == prop
+    assert repr(prop).endswith("kana='さっぽろし')")
+
+
+def test_hierarchy():
+    prop = shibuya()
+    assert prop.level == "class20"
+    assert prop.parent.code == "130011"
+    assert [p.code for p in prop.ancestors()] == ["130011", "130010", "130000", "010300"]
+    assert [p.level for p in prop.ancestors()] == [
+        "class15",
+        "class10",
+        "office",
+        "center",
+    ]
+
+    center = prop.ancestors()[-1]
+    assert center.parent is None
+    assert center.ancestors() == []
+
+
+def test_unknown_code():
+    prop = Property("custom", "テスト")
+    assert prop.en_name == "" and prop.kana is None
+    assert prop.level is None
+    assert prop.parent is None
+    assert Property("13", "東京都").level == "prefecture"
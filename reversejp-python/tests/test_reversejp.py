@@ -0,0 +1,56 @@
+import json
+
+from pytest import raises
+from reversejp import ReverseJp
+
+SQUARE = {
+    "type": "FeatureCollection",
+    "features": [
+        {
+            "type": "Feature",
+            "geometry": {
+                "type": "MultiPolygon",
+                "coordinates": [
+                    [[[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]]]
+                ],
+            },
+            "properties": {"code": "custom", "name": "テスト", "enName": "Test"},
+        }
+    ],
+}
+
+
+def test_embedded():
+    reverse_jp = ReverseJp()
+    properties = reverse_jp.find_properties(139.701402, 35.6585805)
+    assert [p.code for p in properties] == ["130010", "1311300"]
+
+
+def test_empty():
+    assert ReverseJp(embedded=False).find_properties(139.701402, 35.6585805) == []
+
+
+def test_from_geojson():
+    reverse_jp = ReverseJp.from_geojson(json.dumps(SQUARE))
+    properties = reverse_jp.find_properties(5.0, 5.0)
+    assert [(p.code, p.name, p.en_name) for p in properties] == [
+        ("custom", "テスト", "Test")
+    ]
+    assert reverse_jp.find_properties(139.701402, 35.6585805) == []
+
+    combined = ReverseJp.from_geojson(json.dumps(SQUARE), embedded=True)
+    assert len(combined.find_properties(139.701402, 35.6585805)) == 2
+
+
+def test_from_file(tmp_path):
+    path = tmp_path / "square.geojson"
+    path.write_text(json.dumps(SQUARE), encoding="utf-8")
+    reverse_jp = ReverseJp.from_file(path)
+    assert [p.code for p in reverse_jp.find_properties(5.0, 5.0)] == ["custom"]
+
+
+def test_errors(tmp_path):
+    with raises(FileNotFoundError):
+        ReverseJp.from_file(tmp_path / "missing.geojson")
+    with raises(ValueError):
+        ReverseJp.from_geojson("not json")
@@ -0,0 +1,70 @@
+import subprocess
+import sys
+import sysconfig
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+import reversejp
+from reversejp import ReverseJp, find_properties
+
+POINTS = [
+    (139.701402, 35.6585805),  # Shibuya
+    (141.3469, 43.0621),  # Sapporo
+    (135.5023, 34.6937),  # Osaka
+    (127.6809, 26.2124),  # Naha
+    (0.0, 0.0),  # outside Japan
+] * 40
+
+
+def codes(lookup, point):
+    return [p.code for p in lookup(*point)]
+
+
+def run_threaded(lookup, workers=8):
+    with ThreadPoolExecutor(max_workers=workers) as executor:
+        return list(executor.map(lambda point: codes(lookup, point), POINTS))
+
+
+def test_module_function_threads():
+    expected = [codes(find_properties, point) for point in POINTS]
+    assert run_threaded(find_properties) == expected
+    assert "1311300" in expected[0]
+
+
+def test_shared_instance_threads():
+    reverse_jp = ReverseJp()
+    expected = [codes(reverse_jp.find_properties, point) for point in POINTS]
+    assert run_threaded(reverse_jp.find_properties) == expected
+
+
+def test_concurrent_first_use():
+    # The lazily loaded global instance must be created once, whichever thread comes first
+    script = """
+import threading
+from reversejp import find_properties
+
+barrier = threading.Barrier(8)
+results = []
+
+def lookup():
+    barrier.wait()
+    results.append([p.code for p in find_properties(139.701402, 35.6585805)])
+
+threads = [threading.Thread(target=lookup) for _ in range(8)]
+for thread in threads:
+    thread.start()
+for thread in threads:
+    thread.join()
+assert len(results) == 8 and all(r == results[0] for r in results), results
+assert "1311300" in results[0]
+"""
+    subprocess.run([sys.executable, "-c", script], check=True)
+
+
+@pytest.mark.skipif(
+    not sysconfig.get_config_var("Py_GIL_DISABLED"),
+    reason="requires a free-threaded build of Python",
+)
+def test_gil_stays_disabled():
+    assert reversejp.find_properties(139.701402, 35.6585805)
+    assert not sys._is_gil_enabled()
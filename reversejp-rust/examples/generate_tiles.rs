@@ -0,0 +1,37 @@
+use reversejp::{ReverseJp, mvt, simplify};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// Write boundary tiles as `{dir}/{z}/{x}/{y}.mvt`, ready to be served
+// statically. Writing MBTiles/PMTiles archives is left to external tools,
+// which can pack this directory.
+//
+// cargo run --release --example generate_tiles -- tiles 8
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let dir = args.next().unwrap_or_else(|| "tiles".to_string());
+    let max_zoom: u8 = args.next().map(|z| z.parse()).transpose()?.unwrap_or(6);
+
+    let reverse_jp = ReverseJp::with_embedded_data()?;
+
+    // Japan's approximate bounding box: [west, south, east, north]
+    let japan = [122.0, 24.0, 154.0, 46.0];
+
+    for z in 0..=max_zoom {
+        // Drop details smaller than a pixel at this zoom level
+        let simplified = reverse_jp.simplify(simplify::tolerance_for_zoom(z))?;
+
+        let mut count = 0;
+        for (x, y) in mvt::tiles_covering(japan, z)? {
+            let tile = simplified.vector_tile(z, x, y)?;
+            let path = Path::new(&dir).join(format!("{}/{}/{}.mvt", z, x, y));
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, tile)?;
+            count += 1;
+        }
+        println!("Zoom {}: wrote {} tiles", z, count);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+// Region adjacency computed from shared borders
+
+use std::collections::HashMap;
+
+use crate::topology::Topology;
+use crate::{Feature, Properties, ReverseJp};
+
+// Regions of the same level come from the same layer and share the level of
+// their codes, which tells class10s and class20s apart within loaded data
+fn same_level(a: (u32, &Properties), b: (u32, &Properties)) -> bool {
+    a.0 == b.0 && a.1.code.level() == b.1.code.level()
+}
+
+/// Neighbouring regions keyed by region code, from features and their layers
+pub(crate) fn build_adjacency(
+    features: &[Feature],
+    layers: &[u32],
+) -> HashMap<String, Vec<Properties>> {
+    let topology = Topology::build(features);
+
+    // Features using each arc
+    let mut users: Vec<Vec<usize>> = vec![Vec::new(); topology.arcs.len()];
+    for (feature_idx, polygons) in topology.rings.iter().enumerate() {
+        for arc_ref in polygons.iter().flatten().flatten() {
+            let list = &mut users[arc_ref.index];
+            if list.last() != Some(&feature_idx) {
+                list.push(feature_idx);
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    for list in &users {
+        for (i, &a) in list.iter().enumerate() {
+            for &b in &list[i + 1..] {
+                pairs.push((a.min(b), a.max(b)));
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs.dedup();
+
+    let mut adjacency: HashMap<String, Vec<Properties>> = HashMap::new();
+    for (a, b) in pairs {
+        let (a, b) = (
+            (layers[a], &features[a].properties),
+            (layers[b], &features[b].properties),
+        );
+        if a.1.code == b.1.code || !same_level(a, b) {
+            continue;
+        }
+        let (a, b) = (a.1, b.1);
+        for (from, to) in [(a, b), (b, a)] {
+            let neighbours = adjacency.entry(from.code.to_string()).or_default();
+            if !neighbours.iter().any(|p| p.code == to.code) {
+                neighbours.push(to.clone());
+            }
+        }
+    }
+    adjacency
+}
+
+impl ReverseJp {
+    /// Find the regions sharing a border with the given region
+    ///
+    /// Only regions of the same level are returned: class10 areas for a class10
+    /// code, municipalities for a class20 code, regions of the same dissolved
+    /// layer for a dissolved region. Regions touching at a single point are not
+    /// neighbours.
+    ///
+    /// The adjacency graph is computed from the loaded geometries on first use
+    /// and cached until more data is loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The region code, e.g. `130010` or `1310100`
+    ///
+    /// # Returns
+    ///
+    /// The Properties of the neighbouring regions, sorted by code
+    pub fn neighbors(&self, code: &str) -> Vec<Properties> {
+        let adjacency = self.adjacency.get_or_init(|| {
+            let (layers, features): (Vec<u32>, Vec<Feature>) =
+                self.layered_features().into_iter().unzip();
+            build_adjacency(&features, &layers)
+        });
+        let mut neighbours = adjacency.get(code).cloned().unwrap_or_default();
+        neighbours.sort_by(|a, b| a.code.cmp(&b.code));
+        neighbours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::feature;
+
+    fn square(x: f64, y: f64) -> Vec<Vec<[f64; 2]>> {
+        vec![vec![
+            [x, y],
+            [x + 1.0, y],
+            [x + 1.0, y + 1.0],
+            [x, y + 1.0],
+            [x, y],
+        ]]
+    }
+
+    fn codes(props: Vec<Properties>) -> Vec<String> {
+        props.into_iter().map(|p| p.code.into()).collect()
+    }
+
+    #[test]
+    fn test_build_adjacency() {
+        let features = vec![
+            feature("0000001", square(0.0, 0.0)),
+            feature("0000002", square(1.0, 0.0)),
+            // Only touches the first square at its corner
+            feature("0000003", square(1.0, 1.0)),
+            // Shares a border but belongs to another level
+            feature("000004", square(0.0, 1.0)),
+            // Shares a border but belongs to another layer
+            feature("0000005", square(-1.0, 0.0)),
+        ];
+        let adjacency = build_adjacency(&features, &[0, 0, 0, 0, 1]);
+
+        assert_eq!(codes(adjacency["0000001"].clone()), vec!["0000002"]);
+        assert_eq!(
+            codes(adjacency["0000002"].clone()),
+            vec!["0000001", "0000003"]
+        );
+        assert_eq!(codes(adjacency["0000003"].clone()), vec!["0000002"]);
+        assert!(!adjacency.contains_key("000004"));
+        assert!(!adjacency.contains_key("0000005"));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_neighbors_embedded() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        // 千代田区
+        let neighbours = codes(reverse_jp.neighbors("1310100"));
+        for code in ["1310200", "1310300", "1310400", "1310500", "1310600"] {
+            assert!(neighbours.contains(&code.to_string()), "{}", code);
+        }
+        assert!(!neighbours.contains(&"1310100".to_string()));
+        assert!(neighbours.iter().all(|code| code.len() == 7));
+
+        // 東京都 borders 埼玉県南部
+        let neighbours = codes(reverse_jp.neighbors("130010"));
+        assert!(neighbours.contains(&"110010".to_string()));
+        assert!(neighbours.iter().all(|code| code.len() == 6));
+
+        // Neighbourhood is symmetric
+        for code in &neighbours {
+            assert!(codes(reverse_jp.neighbors(code)).contains(&"130010".to_string()));
+        }
+
+        assert!(reverse_jp.neighbors("not-a-code").is_empty());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_neighbors_with_dissolved_layer() {
+        use crate::area::AreaData;
+        use crate::dissolve;
+
+        let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+        reverse_jp
+            .add_dissolved_layer(dissolve::by_office(&area))
+            .unwrap();
+
+        // Offices share 6-digit codes with class10s but stay in their own layer
+        let neighbours = codes(reverse_jp.neighbors("130010"));
+        assert!(neighbours.contains(&"110010".to_string()));
+        assert!(!neighbours.contains(&"110000".to_string()));
+        assert!(!neighbours.contains(&"130000".to_string()));
+
+        let offices = codes(reverse_jp.neighbors("130000"));
+        assert!(offices.contains(&"110000".to_string()));
+        assert!(!offices.contains(&"110010".to_string()));
+    }
+}
@@ -0,0 +1,210 @@
+//! JMA area hierarchy from `area.json`.
+//!
+//! The hierarchy goes, from coarsest to finest: centers (地方), offices
+//! (府県予報区), class10s (一次細分区域), class15s (市町村等をまとめた地域)
+//! and class20s (市町村等).
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AreaLevel, Properties};
+
+/// An entry of `area.json`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Area {
+    pub name: String,
+    #[serde(rename = "enName", default)]
+    pub en_name: String,
+    /// Reading of the name, only present for class20s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kana: Option<String>,
+    /// Name of the responsible observatory, only present for centers and offices
+    #[serde(
+        rename = "officeName",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub office_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<String>,
+}
+
+impl Area {
+    /// The lookup properties of this area
+    pub fn properties(&self, code: &str) -> Properties {
+        Properties {
+            code: code.into(),
+            name: self.name.clone(),
+            en_name: self.en_name.clone(),
+            kana: self.kana.clone(),
+        }
+    }
+}
+
+/// All areas of `area.json`, keyed by code for each level
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AreaData {
+    pub centers: HashMap<String, Area>,
+    pub offices: HashMap<String, Area>,
+    pub class10s: HashMap<String, Area>,
+    pub class15s: HashMap<String, Area>,
+    pub class20s: HashMap<String, Area>,
+}
+
+impl AreaData {
+    /// Load the area hierarchy embedded in the crate
+    #[cfg(feature = "embedded")]
+    pub fn with_embedded_data() -> Result<Self, Box<dyn Error>> {
+        Self::from_json(&crate::get_area_data()?)
+    }
+
+    /// Load an area hierarchy from the JSON of `area.json`
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The areas of one level of the hierarchy, keyed by code
+    ///
+    /// Prefectures are not part of `area.json`, see [`crate::prefecture`].
+    pub fn areas(&self, level: AreaLevel) -> Option<&HashMap<String, Area>> {
+        match level {
+            AreaLevel::Prefecture => None,
+            AreaLevel::Center => Some(&self.centers),
+            AreaLevel::Office => Some(&self.offices),
+            AreaLevel::Class10 => Some(&self.class10s),
+            AreaLevel::Class15 => Some(&self.class15s),
+            AreaLevel::Class20 => Some(&self.class20s),
+        }
+    }
+
+    /// Find an area by code alone
+    ///
+    /// As codes are reused across levels, class20s are searched first, then
+    /// class10s, offices, class15s and centers.
+    pub fn find(&self, code: &str) -> Option<(AreaLevel, &Area)> {
+        [
+            AreaLevel::Class20,
+            AreaLevel::Class10,
+            AreaLevel::Office,
+            AreaLevel::Class15,
+            AreaLevel::Center,
+        ]
+        .into_iter()
+        .find_map(|level| Some((level, self.areas(level)?.get(code)?)))
+    }
+
+    /// Walk up the hierarchy from an area
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The level of the area, needed as codes are reused across levels
+    /// * `code` - The code of the area
+    ///
+    /// # Returns
+    ///
+    /// The level, code and entry of each ancestor, from the parent up to the center
+    pub fn ancestors(&self, level: AreaLevel, code: &str) -> Vec<(AreaLevel, &str, &Area)> {
+        let mut ancestors = Vec::new();
+        let (mut level, mut code) = (level, code);
+        while let Some(parent_level) = parent_level(level) {
+            let Some(parent) = self
+                .areas(level)
+                .and_then(|areas| areas.get(code))
+                .and_then(|area| area.parent.as_deref())
+            else {
+                break;
+            };
+            let Some((parent, area)) = self
+                .areas(parent_level)
+                .and_then(|areas| areas.get_key_value(parent))
+            else {
+                break;
+            };
+            ancestors.push((parent_level, parent.as_str(), area));
+            (level, code) = (parent_level, parent);
+        }
+        ancestors
+    }
+
+    /// Find the office (府県予報区) code a class10, class15 or class20 code
+    /// belongs to
+    ///
+    /// Office codes map to themselves.
+    pub fn office_of(&self, code: &str) -> Option<&str> {
+        if self.offices.contains_key(code) {
+            return Some(self.offices.get_key_value(code)?.0);
+        }
+        let class10 = if let Some(area) = self.class20s.get(code) {
+            self.class15s
+                .get(area.parent.as_deref()?)?
+                .parent
+                .as_deref()?
+        } else if let Some(area) = self.class15s.get(code) {
+            area.parent.as_deref()?
+        } else {
+            code
+        };
+        self.class10s.get(class10)?.parent.as_deref()
+    }
+}
+
+fn parent_level(level: AreaLevel) -> Option<AreaLevel> {
+    match level {
+        AreaLevel::Class20 => Some(AreaLevel::Class15),
+        AreaLevel::Class15 => Some(AreaLevel::Class10),
+        AreaLevel::Class10 => Some(AreaLevel::Office),
+        AreaLevel::Office => Some(AreaLevel::Center),
+        AreaLevel::Center | AreaLevel::Prefecture => None,
+    }
+}
+
+// Every test reads the embedded data
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_area_data() {
+        let area = AreaData::with_embedded_data().unwrap();
+        assert_eq!(area.offices["130000"].name, "東京都");
+        assert_eq!(area.class20s["1311300"].kana.as_deref(), Some("しぶやく"));
+        assert_eq!(area.class10s["130010"].en_name, "Tokyo Region");
+    }
+
+    #[test]
+    fn test_office_of() {
+        let area = AreaData::with_embedded_data().unwrap();
+        assert_eq!(area.office_of("1310100"), Some("130000"));
+        assert_eq!(area.office_of("130010"), Some("130000"));
+        assert_eq!(area.office_of("130000"), Some("130000"));
+        assert_eq!(area.office_of("0110000"), Some("016000"));
+        assert_eq!(area.office_of("not-a-code"), None);
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let area = AreaData::with_embedded_data().unwrap();
+        let codes: Vec<(AreaLevel, &str)> = area
+            .ancestors(AreaLevel::Class20, "1311300")
+            .into_iter()
+            .map(|(level, code, _)| (level, code))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (AreaLevel::Class15, "130011"),
+                (AreaLevel::Class10, "130010"),
+                (AreaLevel::Office, "130000"),
+                (AreaLevel::Center, "010300"),
+            ]
+        );
+        assert!(area.ancestors(AreaLevel::Center, "010300").is_empty());
+        assert_eq!(area.find("011000").unwrap().0, AreaLevel::Class10);
+        assert_eq!(area.find("010300").unwrap().0, AreaLevel::Center);
+        assert!(area.ancestors(AreaLevel::Class20, "not-a-code").is_empty());
+    }
+}
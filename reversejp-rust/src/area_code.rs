@@ -0,0 +1,272 @@
+//! JMA area codes and their conversion to JIS X 0402 municipal codes.
+//!
+//! JMA identifies municipalities (class20s) with 7 digits: the 5-digit JIS
+//! municipal code followed by two digits, `00` for whole municipalities and
+//! non-zero for subdivisions. Government datasets usually carry the 6-digit
+//! JIS code instead, the 5-digit code followed by a check digit.
+//!
+//! ```rust
+//! use reversejp::{AreaCode, AreaLevel};
+//!
+//! let chiyoda: AreaCode = "1310100".parse().unwrap();
+//! assert_eq!(chiyoda.level(), Some(AreaLevel::Class20));
+//! assert_eq!(chiyoda.to_jis6().as_deref(), Some("131016"));
+//! assert_eq!(AreaCode::from_jis("131016").unwrap(), chiyoda);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// The level of the hierarchy an area code belongs to
+///
+/// [`AreaCode::level`] only tells prefectures, class10s and class20s apart:
+/// centers, offices and class15s use the same 6-digit format as class10s,
+/// and their level is only known from `area.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AreaLevel {
+    /// 2-digit JIS prefecture code, e.g. `13`
+    Prefecture,
+    /// Regional center (地方), e.g. `010300`
+    Center,
+    /// Forecast office (府県予報区), e.g. `130000`
+    Office,
+    /// 6-digit JMA region code, e.g. `130010`
+    Class10,
+    /// Group of municipalities, e.g. `130011`
+    Class15,
+    /// 7-digit JMA municipality code, e.g. `1310100`
+    Class20,
+}
+
+/// A JMA area code
+///
+/// Codes read from data files are kept as is, even if they do not follow
+/// the usual format (e.g. `hoppo`). Use [`str::parse`] to validate a code.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct AreaCode(String);
+
+/// Compute the JIS X 0402 check digit of a 5-digit municipal code
+pub fn jis_check_digit(code: &str) -> Option<u8> {
+    if code.len() != 5 || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let sum: u32 = code
+        .bytes()
+        .zip([6, 5, 4, 3, 2])
+        .map(|(b, weight)| u32::from(b - b'0') * weight)
+        .sum();
+    // 11 - remainder, keeping only the last digit of 10 and 11
+    Some(((11 - sum % 11) % 10) as u8)
+}
+
+impl AreaCode {
+    /// Wrap a code without validating it
+    pub fn new(code: impl Into<String>) -> Self {
+        AreaCode(code.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The level of the code, from its format
+    pub fn level(&self) -> Option<AreaLevel> {
+        if !self.0.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        match self.0.len() {
+            2 => Some(AreaLevel::Prefecture),
+            6 => Some(AreaLevel::Class10),
+            7 => Some(AreaLevel::Class20),
+            _ => None,
+        }
+    }
+
+    /// Convert a 5-digit or 6-digit JIS X 0402 municipal code to a class20 code
+    ///
+    /// The check digit of 6-digit codes is verified.
+    pub fn from_jis(jis: &str) -> Result<Self, Box<dyn Error>> {
+        if !jis.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid JIS municipal code: {}", jis).into());
+        }
+        let code5 = match jis.len() {
+            5 => jis,
+            6 => {
+                let expected = jis_check_digit(&jis[..5]).ok_or("Invalid JIS municipal code")?;
+                if jis.as_bytes()[5] != b'0' + expected {
+                    return Err(format!(
+                        "Invalid JIS check digit in {}, expected {}",
+                        jis, expected
+                    )
+                    .into());
+                }
+                &jis[..5]
+            }
+            _ => return Err(format!("Invalid JIS municipal code: {}", jis).into()),
+        };
+        format!("{}00", code5).parse()
+    }
+
+    /// The 5-digit JIS X 0402 code of a class20 code
+    pub fn to_jis5(&self) -> Option<String> {
+        (self.level()? == AreaLevel::Class20).then(|| self.0[..5].to_string())
+    }
+
+    /// The 6-digit JIS X 0402 code, with check digit, of a class20 code
+    pub fn to_jis6(&self) -> Option<String> {
+        let code5 = self.to_jis5()?;
+        let check = jis_check_digit(&code5)?;
+        Some(format!("{}{}", code5, check))
+    }
+}
+
+impl FromStr for AreaCode {
+    type Err = Box<dyn Error>;
+
+    /// Parse a 2-digit prefecture, 6-digit region or 7-digit municipality code
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let code = AreaCode::new(code);
+        let prefecture: u32 = code
+            .get(..2)
+            .filter(|_| code.level().is_some())
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(|| format!("Invalid area code: {}", code))?;
+        if !(1..=47).contains(&prefecture) {
+            return Err(format!("Invalid prefecture number in area code: {}", code).into());
+        }
+        Ok(code)
+    }
+}
+
+impl Deref for AreaCode {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AreaCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for AreaCode {
+    fn from(code: &str) -> Self {
+        AreaCode::new(code)
+    }
+}
+
+impl From<String> for AreaCode {
+    fn from(code: String) -> Self {
+        AreaCode(code)
+    }
+}
+
+impl From<AreaCode> for String {
+    fn from(code: AreaCode) -> Self {
+        code.0
+    }
+}
+
+impl PartialEq<str> for AreaCode {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for AreaCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for AreaCode {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_digit() {
+        assert_eq!(jis_check_digit("13101"), Some(6));
+        assert_eq!(jis_check_digit("01100"), Some(2));
+        assert_eq!(jis_check_digit("27100"), Some(4));
+        // Remainders 0 and 1 give 11 and 10, reduced to their last digit
+        assert_eq!(jis_check_digit("13104"), Some(1));
+        assert_eq!(jis_check_digit("13113"), Some(0));
+        assert_eq!(jis_check_digit("1310"), None);
+        assert_eq!(jis_check_digit("1310a"), None);
+        assert_eq!(jis_check_digit("ああ"), None);
+
+        // Non-digit input is rejected, whatever its length in bytes
+        assert!(AreaCode::from_jis("ああ").is_err());
+        assert!(AreaCode::from_jis("1310a").is_err());
+        assert!(AreaCode::from_jis("13101a").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "13".parse::<AreaCode>().unwrap().level(),
+            Some(AreaLevel::Prefecture)
+        );
+        assert_eq!(
+            "130010".parse::<AreaCode>().unwrap().level(),
+            Some(AreaLevel::Class10)
+        );
+        assert_eq!(
+            "1310100".parse::<AreaCode>().unwrap().level(),
+            Some(AreaLevel::Class20)
+        );
+        assert!("hoppo".parse::<AreaCode>().is_err());
+        assert!("131".parse::<AreaCode>().is_err());
+        assert!("4810100".parse::<AreaCode>().is_err());
+        assert!("0010100".parse::<AreaCode>().is_err());
+        assert_eq!(AreaCode::new("hoppo").level(), None);
+    }
+
+    #[test]
+    fn test_jis_conversion() {
+        let code = AreaCode::from("1310100");
+        assert_eq!(code.to_jis5().as_deref(), Some("13101"));
+        assert_eq!(code.to_jis6().as_deref(), Some("131016"));
+        assert_eq!(AreaCode::from_jis("13101").unwrap(), code);
+        assert_eq!(AreaCode::from_jis("131016").unwrap(), code);
+        assert!(AreaCode::from_jis("131017").is_err());
+        assert!(AreaCode::from_jis("1310").is_err());
+
+        // 新宿区, whose weighted sum is a multiple of 11
+        let shinjuku = AreaCode::from("1310400");
+        assert_eq!(shinjuku.to_jis6().as_deref(), Some("131041"));
+        assert_eq!(AreaCode::from_jis("131041").unwrap(), shinjuku);
+        assert!(AreaCode::from_jis("131040").is_err());
+
+        // Subdivisions map to the JIS code of their municipality
+        assert_eq!(
+            AreaCode::from("0120601").to_jis5().as_deref(),
+            Some("01206")
+        );
+        assert_eq!(AreaCode::from("130010").to_jis6(), None);
+    }
+
+    #[test]
+    fn test_serde_and_comparisons() {
+        let code: AreaCode = serde_json::from_str("\"hoppo\"").unwrap();
+        assert_eq!(code, "hoppo");
+        assert_eq!(serde_json::to_string(&code).unwrap(), "\"hoppo\"");
+        assert!(AreaCode::from("1310100").starts_with("13"));
+        assert_eq!(AreaCode::from("1310100").to_string(), "1310100");
+    }
+}
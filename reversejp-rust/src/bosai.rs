@@ -0,0 +1,393 @@
+//! JMA warning reports from the bosai JSON API.
+//!
+//! JMA publishes the warnings and advisories of each office as JSON at
+//! `https://www.jma.go.jp/bosai/warning/data/warning/{office}.json`, listing
+//! warnings for every class10 and class20 area of the office. This module
+//! parses those documents and resolves the warnings active at a point.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::area::AreaData;
+//! use reversejp::bosai::WarningReport;
+//! use reversejp::ReverseJp;
+//!
+//! let json = r#"{
+//!     "reportDatetime": "2025-07-14T16:03:00+09:00",
+//!     "areaTypes": [
+//!         {"areas": [{"code": "130010", "warnings": [{"code": "14", "status": "発表"}]}]},
+//!         {"areas": [{"code": "1311300", "warnings": [{"code": "14", "status": "発表"}]}]}
+//!     ]
+//! }"#;
+//! let reports = WarningReport::parse(json).unwrap();
+//!
+//! let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//! let area = AreaData::with_embedded_data().unwrap();
+//! let active = reverse_jp.warnings_at(&area, &reports, 139.7016, 35.6619);
+//! assert_eq!(active[0].kind.unwrap().name, "雷注意報");
+//! # }
+//! ```
+
+use std::error::Error;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::area::AreaData;
+use crate::warning::WarningElement;
+use crate::{AreaCode, AreaLevel, ReverseJp};
+
+/// Severity of a warning
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningLevel {
+    /// 注意報
+    Advisory,
+    /// 警報
+    Warning,
+    /// 特別警報
+    Emergency,
+}
+
+/// A warning code used in JMA reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct WarningKind {
+    /// Two-digit code, e.g. `14`
+    pub code: &'static str,
+    /// Japanese name, e.g. `雷注意報`
+    pub name: &'static str,
+    pub level: WarningLevel,
+    /// Elements of `warning.json` the warning is about
+    pub elements: &'static [WarningElement],
+}
+
+macro_rules! warning_kinds {
+    ($(($code:literal, $name:literal, $level:ident, [$($element:ident),*])),* $(,)?) => {
+        /// Every warning code used in JMA reports, ordered by code
+        pub const WARNING_KINDS: &[WarningKind] = &[$(WarningKind {
+            code: $code,
+            name: $name,
+            level: WarningLevel::$level,
+            elements: &[$(WarningElement::$element),*],
+        }),*];
+    };
+}
+
+warning_kinds![
+    ("02", "暴風雪警報", Warning, [WindSnow]),
+    ("03", "大雨警報", Warning, [Inundation, Landslide]),
+    ("04", "洪水警報", Warning, [Flood]),
+    ("05", "暴風警報", Warning, [Wind]),
+    ("06", "大雪警報", Warning, [Snow]),
+    ("07", "波浪警報", Warning, [Wave]),
+    ("08", "高潮警報", Warning, [Tide]),
+    ("10", "大雨注意報", Advisory, [Inundation, Landslide]),
+    ("12", "大雪注意報", Advisory, [Snow]),
+    ("13", "風雪注意報", Advisory, [WindSnow]),
+    ("14", "雷注意報", Advisory, [Thunder]),
+    ("15", "強風注意報", Advisory, [Wind]),
+    ("16", "波浪注意報", Advisory, [Wave]),
+    ("17", "融雪注意報", Advisory, [SnowMelting]),
+    ("18", "洪水注意報", Advisory, [Flood]),
+    ("19", "高潮注意報", Advisory, [Tide]),
+    ("20", "濃霧注意報", Advisory, [Fog]),
+    ("21", "乾燥注意報", Advisory, [Dry]),
+    ("22", "なだれ注意報", Advisory, [Avalanche]),
+    ("23", "低温注意報", Advisory, [Cold]),
+    ("24", "霜注意報", Advisory, [Frost]),
+    ("25", "着氷注意報", Advisory, [IceAccretion]),
+    ("26", "着雪注意報", Advisory, [SnowAccretion]),
+    ("27", "その他の注意報", Advisory, []),
+    ("32", "暴風雪特別警報", Emergency, [WindSnow]),
+    ("33", "大雨特別警報", Emergency, [Inundation, Landslide]),
+    ("35", "暴風特別警報", Emergency, [Wind]),
+    ("36", "大雪特別警報", Emergency, [Snow]),
+    ("37", "波浪特別警報", Emergency, [Wave]),
+    ("38", "高潮特別警報", Emergency, [Tide]),
+];
+
+/// Find a warning kind by its two-digit code
+pub fn warning_kind(code: &str) -> Option<&'static WarningKind> {
+    WARNING_KINDS.iter().find(|kind| kind.code == code)
+}
+
+/// A warning listed for an area
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Warning {
+    /// Warning code, absent when the area has no warnings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Status such as `発表` (issued), `継続` (continued) or `解除` (lifted)
+    pub status: String,
+    /// Points of attention, e.g. `落雷注意`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attentions: Vec<String>,
+}
+
+impl Warning {
+    /// Whether the warning is in effect
+    pub fn is_active(&self) -> bool {
+        self.code.is_some() && self.status != "解除" && self.status != "発表警報・注意報はなし"
+    }
+
+    /// The kind of warning, if the code is known
+    pub fn kind(&self) -> Option<&'static WarningKind> {
+        warning_kind(self.code.as_deref()?)
+    }
+}
+
+/// The warnings of one area
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AreaWarnings {
+    pub code: AreaCode,
+    #[serde(default)]
+    pub warnings: Vec<Warning>,
+}
+
+/// The areas of one level (class10s or class20s) of a report
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AreaType {
+    pub areas: Vec<AreaWarnings>,
+}
+
+/// A warning report of one office
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WarningReport {
+    #[serde(rename = "reportDatetime")]
+    pub report_datetime: String,
+    #[serde(rename = "publishingOffice", default)]
+    pub publishing_office: String,
+    #[serde(rename = "headlineText", default)]
+    pub headline_text: String,
+    #[serde(rename = "areaTypes", default)]
+    pub area_types: Vec<AreaType>,
+}
+
+/// A warning in effect for a point
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ActiveWarning {
+    /// The area the warning was issued for
+    pub area: AreaCode,
+    pub warning: Warning,
+    pub kind: Option<&'static WarningKind>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Reports {
+    One(WarningReport),
+    Many(Vec<WarningReport>),
+}
+
+impl WarningReport {
+    /// Parse a report, or a list of reports, from JSON
+    pub fn parse(json: &str) -> Result<Vec<Self>, Box<dyn Error>> {
+        Ok(match serde_json::from_str(json)? {
+            Reports::One(report) => vec![report],
+            Reports::Many(reports) => reports,
+        })
+    }
+
+    /// Read a report, or a list of reports, from a JSON file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Vec<Self>, Box<dyn Error>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// The warnings listed for an area, if the report covers it
+    pub fn area(&self, code: &str) -> Option<&AreaWarnings> {
+        self.area_types
+            .iter()
+            .flat_map(|area_type| &area_type.areas)
+            .find(|area| area.code == code)
+    }
+}
+
+/// Find the warnings in effect for an area
+///
+/// When no report lists the area itself, the warnings of the closest
+/// ancestor listed (usually the class10 of a class20) are used.
+///
+/// # Arguments
+///
+/// * `reports` - Parsed warning reports
+/// * `area` - The area hierarchy, used to find ancestors
+/// * `level` - The level of the area
+/// * `code` - The code of the area
+///
+/// # Returns
+///
+/// The active warnings, most severe first
+pub fn active_warnings(
+    reports: &[WarningReport],
+    area: &AreaData,
+    level: AreaLevel,
+    code: &str,
+) -> Vec<ActiveWarning> {
+    let listed = |code: &str| reports.iter().find_map(|report| report.area(code));
+    let Some(area_warnings) = listed(code).or_else(|| {
+        area.ancestors(level, code)
+            .into_iter()
+            .find_map(|(_, code, _)| listed(code))
+    }) else {
+        return Vec::new();
+    };
+
+    let mut active: Vec<ActiveWarning> = area_warnings
+        .warnings
+        .iter()
+        .filter(|warning| warning.is_active())
+        .map(|warning| ActiveWarning {
+            area: area_warnings.code.clone(),
+            warning: warning.clone(),
+            kind: warning.kind(),
+        })
+        .collect();
+    active.sort_by_key(|w| std::cmp::Reverse(w.kind.map(|kind| kind.level)));
+    active
+}
+
+impl ReverseJp {
+    /// Find the warnings in effect at a point
+    ///
+    /// Municipal (class20) warnings are used when the point falls in a
+    /// municipality, otherwise those of its class10 area.
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - The area hierarchy, used to find the class10 of municipalities
+    ///   missing from the reports
+    /// * `reports` - Parsed warning reports
+    /// * `longitude` - The longitude coordinate
+    /// * `latitude` - The latitude coordinate
+    ///
+    /// # Returns
+    ///
+    /// The active warnings, most severe first
+    pub fn warnings_at(
+        &self,
+        area: &AreaData,
+        reports: &[WarningReport],
+        longitude: f64,
+        latitude: f64,
+    ) -> Vec<ActiveWarning> {
+        let props = self.find_properties(longitude, latitude);
+        let class20s: Vec<&str> = props
+            .iter()
+            .filter(|p| p.code.level() == Some(AreaLevel::Class20))
+            .map(|p| p.code.as_str())
+            .collect();
+        let (level, codes) = if class20s.is_empty() {
+            let class10s = props
+                .iter()
+                .filter(|p| p.code.level() == Some(AreaLevel::Class10))
+                .map(|p| p.code.as_str())
+                .collect();
+            (AreaLevel::Class10, class10s)
+        } else {
+            (AreaLevel::Class20, class20s)
+        };
+
+        let mut active: Vec<ActiveWarning> = Vec::new();
+        for code in codes {
+            for warning in active_warnings(reports, area, level, code) {
+                if !active
+                    .iter()
+                    .any(|w| w.area == warning.area && w.warning.code == warning.warning.code)
+                {
+                    active.push(warning);
+                }
+            }
+        }
+        active.sort_by_key(|w| std::cmp::Reverse(w.kind.map(|kind| kind.level)));
+        active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/warning_130000.json");
+
+    #[cfg(feature = "embedded")]
+    fn codes(warnings: &[ActiveWarning]) -> Vec<(&str, &str)> {
+        warnings
+            .iter()
+            .map(|w| (w.area.as_str(), w.warning.code.as_deref().unwrap_or("")))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let reports = WarningReport::parse(FIXTURE).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].publishing_office, "気象庁");
+        let chiyoda = reports[0].area("1310100").unwrap();
+        assert_eq!(chiyoda.warnings.len(), 2);
+        assert!(chiyoda.warnings[0].is_active());
+        assert!(!chiyoda.warnings[1].is_active());
+        assert!(!reports[0].area("1310300").unwrap().warnings[0].is_active());
+
+        let list = format!("[{}, {}]", FIXTURE, FIXTURE);
+        assert_eq!(WarningReport::parse(&list).unwrap().len(), 2);
+        assert!(WarningReport::parse("{}").is_err());
+    }
+
+    #[test]
+    fn test_warning_kinds() {
+        assert_eq!(warning_kind("03").unwrap().level, WarningLevel::Warning);
+        assert_eq!(
+            warning_kind("33").unwrap().elements,
+            [WarningElement::Inundation, WarningElement::Landslide]
+        );
+        assert!(warning_kind("27").unwrap().elements.is_empty());
+        assert!(warning_kind("99").is_none());
+        assert!(WARNING_KINDS.windows(2).all(|w| w[0].code < w[1].code));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_active_warnings_hierarchy() {
+        let reports = WarningReport::parse(FIXTURE).unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+
+        // 大島町 is not listed, its class10 (伊豆諸島北部) is
+        let oshima = active_warnings(&reports, &area, AreaLevel::Class20, "1336100");
+        assert_eq!(
+            codes(&oshima),
+            vec![("130020", "03"), ("130020", "15"), ("130020", "16")]
+        );
+        // 小笠原村, under その他の注意報 with no element of its own
+        let ogasawara = active_warnings(&reports, &area, AreaLevel::Class20, "1342100");
+        assert_eq!(codes(&ogasawara), vec![("130040", "27")]);
+        assert_eq!(ogasawara[0].kind.unwrap().name, "その他の注意報");
+        assert!(active_warnings(&reports, &area, AreaLevel::Class20, "1310300").is_empty());
+        assert!(active_warnings(&reports, &area, AreaLevel::Class20, "2710000").is_empty());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_warnings_at() {
+        let reports = WarningReport::from_file(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/warning_130000.json"),
+        )
+        .unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        // Shibuya
+        let active = reverse_jp.warnings_at(&area, &reports, 139.7016, 35.6619);
+        assert_eq!(codes(&active), vec![("1311300", "14"), ("1311300", "21")]);
+
+        // Imperial Palace, in Chiyoda where the dry air advisory was lifted
+        let active = reverse_jp.warnings_at(&area, &reports, 139.7528, 35.6852);
+        assert_eq!(codes(&active), vec![("1310100", "14")]);
+        assert_eq!(active[0].warning.attentions[0], "落雷注意");
+
+        // Osaka is not covered by the report
+        assert!(
+            reverse_jp
+                .warnings_at(&area, &reports, 135.5023, 34.6937)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,597 @@
+//! JMA disaster prevention XML bulletins (気象庁防災情報XML).
+//!
+//! Bulletins reference areas with `<Area><Code>` elements (and `<Pref>` and
+//! `<City>` for seismic intensities). This module reads the areas of weather
+//! warnings, landslide alerts and earthquake intensity bulletins and resolves
+//! their codes to the names, hierarchy and geometries of reversejp.
+//!
+//! Requires the `jma-xml` feature.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::area::AreaData;
+//! use reversejp::bulletin::{Bulletin, BulletinType};
+//! use reversejp::ReverseJp;
+//!
+//! let xml = r#"<Report xmlns="http://xml.kishou.go.jp/jmaxml1/">
+//!   <Control><Title>土砂災害警戒情報</Title></Control>
+//!   <Body>
+//!     <Warning type="土砂災害警戒情報">
+//!       <Item>
+//!         <Kind><Name>警戒</Name><Code>3</Code><Status>発表</Status></Kind>
+//!         <Area><Name>渋谷区</Name><Code>1311300</Code></Area>
+//!       </Item>
+//!     </Warning>
+//!   </Body>
+//! </Report>"#;
+//! let bulletin = Bulletin::parse(xml).unwrap();
+//! assert_eq!(bulletin.bulletin_type, BulletinType::LandslideAlert);
+//!
+//! let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//! let area = AreaData::with_embedded_data().unwrap();
+//! let areas = reverse_jp.bulletin_areas_at(&bulletin, &area, 139.7016, 35.6619);
+//! assert!(areas[0].is_active());
+//! # }
+//! ```
+
+use std::error::Error;
+use std::path::Path;
+
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde::Serialize;
+
+use crate::area::AreaData;
+use crate::prefecture::prefecture;
+use crate::{AreaCode, AreaLevel, Geometry, Properties, ReverseJp};
+
+/// The kind of bulletin, from its title
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulletinType {
+    /// 気象警報・注意報, 気象特別警報
+    Warning,
+    /// 土砂災害警戒情報
+    LandslideAlert,
+    /// 震度速報, 震源・震度に関する情報
+    Intensity,
+    Other,
+}
+
+/// A warning or alert issued for an area
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AlertKind {
+    /// Name, e.g. `大雨警報` or `警戒`
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Status, e.g. `発表`, `継続` or `解除`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+impl AlertKind {
+    /// Whether the warning or alert is in effect
+    pub fn is_active(&self) -> bool {
+        !matches!(
+            self.status.as_deref(),
+            Some("解除" | "なし" | "発表警報・注意報はなし")
+        )
+    }
+}
+
+/// An area referenced by a bulletin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BulletinArea {
+    /// The section the area was listed in: the `type` of the enclosing
+    /// `<Warning>`, e.g. `気象警報・注意報（市町村等）`, or `Pref`, `Area` and
+    /// `City` for seismic intensities
+    pub section: String,
+    pub code: AreaCode,
+    pub name: String,
+    /// Warnings or alerts issued for the area
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<AlertKind>,
+    /// Maximum seismic intensity observed in the area, e.g. `5-`
+    #[serde(rename = "maxInt", skip_serializing_if = "Option::is_none")]
+    pub max_intensity: Option<String>,
+}
+
+impl BulletinArea {
+    /// Whether a warning or alert is in effect, or an intensity was observed
+    pub fn is_active(&self) -> bool {
+        self.kinds.iter().any(AlertKind::is_active) || self.max_intensity.is_some()
+    }
+
+    /// The level of the area in the reversejp hierarchy
+    ///
+    /// Seismic subdivision areas (`地震情報／細分区域`) are not part of the
+    /// hierarchy and have no level.
+    pub fn level(&self, area: &AreaData) -> Option<AreaLevel> {
+        let section = self.section.as_str();
+        if section == "Area" {
+            return None;
+        }
+        if section == "Pref" {
+            return Some(AreaLevel::Prefecture);
+        }
+        if section.contains("府県予報区") {
+            return Some(AreaLevel::Office);
+        }
+        if section.contains("一次細分区域") {
+            return Some(AreaLevel::Class10);
+        }
+        if section.contains("市町村等をまとめた地域") {
+            return Some(AreaLevel::Class15);
+        }
+        match self.code.level()? {
+            AreaLevel::Prefecture => Some(AreaLevel::Prefecture),
+            AreaLevel::Class20 => Some(AreaLevel::Class20),
+            _ => area.find(&self.code).map(|(level, _)| level),
+        }
+    }
+}
+
+/// A parsed bulletin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bulletin {
+    /// `Control/Title`, e.g. `気象警報・注意報（Ｈ２７）`
+    pub title: String,
+    #[serde(rename = "bulletinType")]
+    pub bulletin_type: BulletinType,
+    /// `Head/ReportDateTime`
+    #[serde(rename = "reportDatetime")]
+    pub report_datetime: String,
+    /// `Head/InfoType`, e.g. `発表` or `取消`
+    #[serde(rename = "infoType")]
+    pub info_type: String,
+    /// Areas listed in the body, in document order
+    pub areas: Vec<BulletinArea>,
+}
+
+/// An area of a bulletin resolved against reversejp data
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedArea<'a> {
+    pub area: &'a BulletinArea,
+    pub level: Option<AreaLevel>,
+    /// Names of the area, `None` if the code is unknown
+    pub properties: Option<Properties>,
+    /// Ancestors of the area, from the parent up to the center
+    pub hierarchy: Vec<Properties>,
+    /// Geometry of the area, for class10s and class20s of the loaded layers
+    pub geometry: Option<Geometry>,
+}
+
+// Minimal XML tree, enough to walk bulletins by local element names
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn child_text(&self, name: &str) -> Option<&str> {
+        self.child(name).map(|child| child.text.trim())
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn descendants<'a>(&'a self, name: &str, out: &mut Vec<&'a Element>) {
+        for child in &self.children {
+            if child.name == name {
+                out.push(child);
+            }
+            child.descendants(name, out);
+        }
+    }
+}
+
+fn element(start: &BytesStart) -> Result<Element, Box<dyn Error>> {
+    let mut element = Element {
+        name: start.local_name().as_ref().to_string(),
+        ..Default::default()
+    };
+    for attribute in start.attributes() {
+        let attribute = attribute?;
+        element.attributes.push((
+            attribute.key.local_name().as_ref().to_string(),
+            attribute
+                .normalized_value(XmlVersion::Implicit1_0)?
+                .into_owned(),
+        ));
+    }
+    Ok(element)
+}
+
+fn parse_xml(xml: &str) -> Result<Element, Box<dyn Error>> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack = vec![Element::default()];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => stack.push(element(&start)?),
+            Event::Empty(start) => {
+                let element = element(&start)?;
+                stack
+                    .last_mut()
+                    .ok_or("Unbalanced XML")?
+                    .children
+                    .push(element);
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or("Unbalanced XML")?;
+                stack
+                    .last_mut()
+                    .ok_or("Unbalanced XML")?
+                    .children
+                    .push(element);
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.xml10_content());
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&data);
+                }
+            }
+            Event::GeneralRef(reference) => {
+                let resolved = match reference.resolve_char_ref()? {
+                    Some(c) => c.to_string(),
+                    None => resolve_predefined_entity(&reference)
+                        .ok_or_else(|| format!("Unknown XML entity: {}", &*reference))?
+                        .to_string(),
+                };
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&resolved);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let mut document = stack.pop().ok_or("Unbalanced XML")?;
+    if !stack.is_empty() {
+        return Err("Unexpected end of XML".into());
+    }
+    document
+        .children
+        .pop()
+        .ok_or_else(|| "Empty XML document".into())
+}
+
+fn bulletin_type(title: &str) -> BulletinType {
+    if title.contains("土砂災害警戒情報") {
+        BulletinType::LandslideAlert
+    } else if title.contains("気象警報") || title.contains("気象特別警報") {
+        BulletinType::Warning
+    } else if title.contains("震度") {
+        BulletinType::Intensity
+    } else {
+        BulletinType::Other
+    }
+}
+
+fn bulletin_area(section: &str, element: &Element) -> Option<BulletinArea> {
+    Some(BulletinArea {
+        section: section.to_string(),
+        code: element.child_text("Code")?.into(),
+        name: element.child_text("Name").unwrap_or_default().to_string(),
+        kinds: Vec::new(),
+        max_intensity: None,
+    })
+}
+
+impl Bulletin {
+    /// Parse a bulletin from its XML
+    pub fn parse(xml: &str) -> Result<Self, Box<dyn Error>> {
+        let report = parse_xml(xml)?;
+        if report.name != "Report" {
+            return Err(format!("Expected a Report element, found {}", report.name).into());
+        }
+        let title = report
+            .child("Control")
+            .and_then(|control| control.child_text("Title"))
+            .ok_or("Missing Control/Title")?
+            .to_string();
+        let head = report.child("Head");
+        let head_text = |name| {
+            head.and_then(|head| head.child_text(name))
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let mut areas = Vec::new();
+        if let Some(body) = report.child("Body") {
+            // Warnings and landslide alerts list kinds and areas per item
+            let mut sections = Vec::new();
+            body.descendants("Warning", &mut sections);
+            for section in sections {
+                let section_type = section.attribute("type").unwrap_or_default();
+                for item in section.children("Item") {
+                    let kinds: Vec<AlertKind> = item
+                        .children("Kind")
+                        .map(|kind| AlertKind {
+                            name: kind.child_text("Name").unwrap_or_default().to_string(),
+                            code: kind.child_text("Code").map(str::to_string),
+                            status: kind.child_text("Status").map(str::to_string),
+                        })
+                        .collect();
+                    let listed = item
+                        .children("Area")
+                        .chain(item.children("Areas").flat_map(|a| a.children("Area")));
+                    for element in listed {
+                        if let Some(mut area) = bulletin_area(section_type, element) {
+                            area.kinds = kinds.clone();
+                            areas.push(area);
+                        }
+                    }
+                }
+            }
+
+            // Seismic intensities are nested as Pref > Area > City
+            if let Some(intensity) = body.child("Intensity") {
+                for observation in intensity.children("Observation") {
+                    for pref in observation.children("Pref") {
+                        let nested = pref.children("Area").flat_map(|area| {
+                            std::iter::once(("Area", area))
+                                .chain(area.children("City").map(|city| ("City", city)))
+                        });
+                        for (section, element) in std::iter::once(("Pref", pref)).chain(nested) {
+                            if let Some(mut area) = bulletin_area(section, element) {
+                                area.max_intensity =
+                                    element.child_text("MaxInt").map(str::to_string);
+                                areas.push(area);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Bulletin {
+            bulletin_type: bulletin_type(&title),
+            title,
+            report_datetime: head_text("ReportDateTime"),
+            info_type: head_text("InfoType"),
+            areas,
+        })
+    }
+
+    /// Read a bulletin from an XML file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Resolve every area of the bulletin to names, hierarchy and geometry
+    ///
+    /// # Arguments
+    ///
+    /// * `reverse_jp` - Loaded layers, used for geometries
+    /// * `area` - The area hierarchy, used for names and ancestors
+    ///
+    /// # Returns
+    ///
+    /// One entry per area of the bulletin, in document order
+    pub fn resolve(&self, reverse_jp: &ReverseJp, area: &AreaData) -> Vec<ResolvedArea<'_>> {
+        self.areas
+            .iter()
+            .map(|bulletin_area| {
+                let level = bulletin_area.level(area);
+                let code = bulletin_area.code.as_str();
+                let properties = match level {
+                    Some(AreaLevel::Prefecture) => prefecture(code).map(|p| Properties {
+                        code: p.code.into(),
+                        name: p.name.to_string(),
+                        en_name: p.en_name.to_string(),
+                        kana: None,
+                    }),
+                    Some(level) => area
+                        .areas(level)
+                        .and_then(|areas| areas.get(code))
+                        .map(|entry| entry.properties(code)),
+                    None => None,
+                };
+                let hierarchy = level
+                    .map(|level| area.ancestors(level, code))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(_, code, ancestor)| ancestor.properties(code))
+                    .collect();
+                let geometry = match level {
+                    Some(AreaLevel::Class10 | AreaLevel::Class20) => reverse_jp.geometry(code),
+                    _ => None,
+                };
+                ResolvedArea {
+                    area: bulletin_area,
+                    level,
+                    properties,
+                    hierarchy,
+                    geometry,
+                }
+            })
+            .collect()
+    }
+}
+
+impl ReverseJp {
+    /// Find the areas of a bulletin that cover a point
+    ///
+    /// An area covers the point when it is one of the regions found at the
+    /// point, one of their ancestors, or their prefecture. Seismic
+    /// subdivision areas are never matched.
+    ///
+    /// # Arguments
+    ///
+    /// * `bulletin` - A parsed bulletin
+    /// * `area` - The area hierarchy, used to find ancestors
+    /// * `longitude` - The longitude coordinate
+    /// * `latitude` - The latitude coordinate
+    ///
+    /// # Returns
+    ///
+    /// The matching areas, in document order; check
+    /// [`BulletinArea::is_active`] for the ones in effect
+    pub fn bulletin_areas_at<'a>(
+        &self,
+        bulletin: &'a Bulletin,
+        area: &AreaData,
+        longitude: f64,
+        latitude: f64,
+    ) -> Vec<&'a BulletinArea> {
+        let mut codes: Vec<String> = Vec::new();
+        for props in self.find_properties(longitude, latitude) {
+            if let Some(level) = props.code.level() {
+                codes.extend(
+                    area.ancestors(level, &props.code)
+                        .into_iter()
+                        .map(|(_, code, _)| code.to_string()),
+                );
+            }
+            if let Some(prefecture) = props.prefecture() {
+                codes.push(prefecture.code.to_string());
+            }
+            codes.push(props.code.into());
+        }
+        bulletin
+            .areas
+            .iter()
+            .filter(|a| a.section != "Area" && codes.iter().any(|code| a.code == *code))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WARNING: &str = include_str!("../tests/fixtures/jma_warning.xml");
+    const LANDSLIDE: &str = include_str!("../tests/fixtures/jma_landslide.xml");
+    const INTENSITY: &str = include_str!("../tests/fixtures/jma_intensity.xml");
+
+    fn codes<'a>(areas: impl IntoIterator<Item = &'a BulletinArea>) -> Vec<&'a str> {
+        areas.into_iter().map(|a| a.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_warning() {
+        let bulletin = Bulletin::parse(WARNING).unwrap();
+        assert_eq!(bulletin.bulletin_type, BulletinType::Warning);
+        assert_eq!(bulletin.report_datetime, "2025-07-14T16:03:00+09:00");
+        assert_eq!(bulletin.info_type, "発表");
+        // Headline areas are not repeated
+        assert_eq!(
+            codes(&bulletin.areas),
+            vec!["130000", "130010", "130020", "1310100", "1336100"]
+        );
+        let chiyoda = &bulletin.areas[3];
+        assert_eq!(chiyoda.kinds.len(), 2);
+        assert!(chiyoda.kinds[0].is_active());
+        assert!(!chiyoda.kinds[1].is_active());
+        assert_eq!(chiyoda.section, "気象警報・注意報（市町村等）");
+    }
+
+    #[test]
+    fn test_parse_landslide_and_intensity() {
+        let landslide = Bulletin::parse(LANDSLIDE).unwrap();
+        assert_eq!(landslide.bulletin_type, BulletinType::LandslideAlert);
+        assert_eq!(codes(&landslide.areas), vec!["1336100", "1336200"]);
+        assert!(landslide.areas[0].is_active());
+        assert!(!landslide.areas[1].is_active());
+
+        let intensity = Bulletin::parse(INTENSITY).unwrap();
+        assert_eq!(intensity.bulletin_type, BulletinType::Intensity);
+        assert_eq!(
+            codes(&intensity.areas),
+            vec!["13", "350", "1310100", "1311300"]
+        );
+        assert_eq!(intensity.areas[2].max_intensity.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Bulletin::parse("<Report><Control>").is_err());
+        assert!(Bulletin::parse("<Other/>").is_err());
+        assert!(Bulletin::parse("<Report></Report>").is_err());
+        let escaped =
+            Bulletin::parse("<Report><Control><Title>A &amp; B&#x21;</Title></Control></Report>")
+                .unwrap();
+        assert_eq!(escaped.title, "A & B!");
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_resolve() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+
+        let bulletin = Bulletin::parse(WARNING).unwrap();
+        let resolved = bulletin.resolve(&reverse_jp, &area);
+        let levels: Vec<Option<AreaLevel>> = resolved.iter().map(|r| r.level).collect();
+        assert_eq!(
+            levels,
+            vec![
+                Some(AreaLevel::Office),
+                Some(AreaLevel::Class10),
+                Some(AreaLevel::Class10),
+                Some(AreaLevel::Class20),
+                Some(AreaLevel::Class20),
+            ]
+        );
+        assert!(resolved[0].geometry.is_none());
+        assert!(resolved[1].geometry.is_some());
+        let oshima = &resolved[4];
+        assert_eq!(oshima.properties.as_ref().unwrap().en_name, "Oshima Town");
+        assert_eq!(oshima.hierarchy[1].code, "130020");
+        assert!(oshima.geometry.is_some());
+
+        let intensity = Bulletin::parse(INTENSITY).unwrap();
+        let resolved = intensity.resolve(&reverse_jp, &area);
+        assert_eq!(resolved[0].properties.as_ref().unwrap().name, "東京都");
+        assert_eq!(resolved[1].level, None);
+        assert!(resolved[1].properties.is_none());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_bulletin_areas_at() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+        // Imperial Palace, in Chiyoda
+        let (lng, lat) = (139.7528, 35.6852);
+        let warning = Bulletin::from_file(fixtures.join("jma_warning.xml")).unwrap();
+        assert_eq!(
+            codes(reverse_jp.bulletin_areas_at(&warning, &area, lng, lat)),
+            vec!["130000", "130010", "1310100"]
+        );
+        let landslide = Bulletin::from_file(fixtures.join("jma_landslide.xml")).unwrap();
+        assert!(
+            reverse_jp
+                .bulletin_areas_at(&landslide, &area, lng, lat)
+                .is_empty()
+        );
+        let intensity = Bulletin::from_file(fixtures.join("jma_intensity.xml")).unwrap();
+        assert_eq!(
+            codes(reverse_jp.bulletin_areas_at(&intensity, &area, lng, lat)),
+            vec!["13", "1310100"]
+        );
+    }
+}
@@ -0,0 +1,390 @@
+//! Dissolve municipalities into larger regions.
+//!
+//! Municipalities (class20s) are grouped by a key function returning the
+//! properties of their group, and the borders shared inside each group are
+//! removed. The resulting regions can be registered as a new queryable layer.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::{ReverseJp, dissolve};
+//!
+//! let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//! reverse_jp.add_dissolved_layer(dissolve::by_prefecture).unwrap();
+//!
+//! let props = reverse_jp.find_properties(139.7670, 35.6812);
+//! assert!(props.iter().any(|p| p.code == "13"));
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use crate::area::AreaData;
+use crate::topology::Topology;
+use crate::{AreaLevel, Feature, FeatureCollection, Geometry, Properties, ReverseJp, SOURCE_LAYER};
+
+/// Group municipalities by prefecture, using the JIS prefecture number that
+/// starts their code (`1310100` belongs to `13`)
+pub fn by_prefecture(props: &Properties) -> Option<Properties> {
+    if props.code.level() != Some(AreaLevel::Class20) {
+        return None;
+    }
+    let prefecture = props.prefecture()?;
+    Some(Properties {
+        code: prefecture.code.into(),
+        name: prefecture.name.to_string(),
+        en_name: prefecture.en_name.to_string(),
+        kana: None,
+    })
+}
+
+/// Group municipalities by forecast office (府県予報区) from `area.json`
+///
+/// Municipalities whose office is missing from `area` are left out.
+pub fn by_office(area: &AreaData) -> impl Fn(&Properties) -> Option<Properties> + '_ {
+    move |props| {
+        if props.code.level() != Some(AreaLevel::Class20) {
+            return None;
+        }
+        let code = area.office_of(&props.code)?;
+        let office = area.offices.get(code)?;
+        Some(Properties {
+            code: code.into(),
+            name: office.name.clone(),
+            en_name: office.en_name.clone(),
+            kana: None,
+        })
+    }
+}
+
+// Planar signed area of a closed ring
+fn signed_area(ring: &[[f64; 2]]) -> f64 {
+    ring.windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum::<f64>()
+        / 2.0
+}
+
+fn ring_contains(ring: &[[f64; 2]], p: [f64; 2]) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        if (a[1] > p[1]) != (b[1] > p[1])
+            && p[0] < (b[0] - a[0]) * (p[1] - a[1]) / (b[1] - a[1]) + a[0]
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+// Chain boundary arcs into closed rings
+fn stitch(arcs: &[&Vec<[f64; 2]>]) -> Vec<Vec<[f64; 2]>> {
+    type Key = (u64, u64);
+    let key = |c: [f64; 2]| (c[0].to_bits(), c[1].to_bits());
+
+    let mut ends: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, arc) in arcs.iter().enumerate() {
+        ends.entry(key(arc[0])).or_default().push(i);
+        ends.entry(key(arc[arc.len() - 1])).or_default().push(i);
+    }
+
+    let mut used = vec![false; arcs.len()];
+    let mut rings = Vec::new();
+    for start in 0..arcs.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut ring: Vec<[f64; 2]> = arcs[start].to_vec();
+        let first: Key = key(ring[0]);
+
+        while key(ring[ring.len() - 1]) != first {
+            let tail = key(ring[ring.len() - 1]);
+            let next = ends
+                .get(&tail)
+                .and_then(|list| list.iter().copied().find(|&i| !used[i]));
+            let Some(next) = next else {
+                // Broken boundary, close the ring as is
+                ring.push(ring[0]);
+                break;
+            };
+            used[next] = true;
+            let arc = arcs[next];
+            if key(arc[0]) == tail {
+                ring.extend(arc.iter().skip(1));
+            } else {
+                ring.extend(arc.iter().rev().skip(1));
+            }
+        }
+
+        if ring.len() >= 4 {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+// Nest rings into polygons: a ring inside an odd number of rings is a hole
+fn assemble(mut rings: Vec<Vec<[f64; 2]>>) -> Vec<Vec<Vec<[f64; 2]>>> {
+    rings.sort_by(|a, b| signed_area(b).abs().total_cmp(&signed_area(a).abs()));
+
+    let mut polygons: Vec<Vec<Vec<[f64; 2]>>> = Vec::new();
+    // For every ring placed so far: polygon index and nesting depth
+    let mut placed: Vec<(usize, usize)> = Vec::new();
+    for (i, mut ring) in rings.iter().cloned().enumerate() {
+        let parents: Vec<usize> = (0..i)
+            .filter(|&j| ring_contains(&rings[j], ring[0]))
+            .collect();
+        let depth = parents.len();
+        // Exteriors counter-clockwise, holes clockwise, as in RFC 7946
+        let ccw = signed_area(&ring) > 0.0;
+        if depth % 2 == 1 {
+            if ccw {
+                ring.reverse();
+            }
+            // The innermost containing ring is the smallest one
+            let parent = *parents.last().unwrap();
+            let polygon = placed[parent].0;
+            polygons[polygon].push(ring);
+            placed.push((polygon, depth));
+        } else {
+            if !ccw {
+                ring.reverse();
+            }
+            polygons.push(vec![ring]);
+            placed.push((polygons.len() - 1, depth));
+        }
+    }
+    polygons
+}
+
+impl ReverseJp {
+    /// Dissolve municipalities into groups
+    ///
+    /// `key` returns the properties of the group a region belongs to, or `None`
+    /// to leave the region out. Only class20 regions are passed to `key`.
+    /// Borders shared inside a group are removed and the remaining borders are
+    /// assembled into one MultiPolygon per group.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "embedded")] {
+    /// use reversejp::{Properties, ReverseJp};
+    ///
+    /// let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+    /// // Tokyo's 23 special wards
+    /// let wards = reverse_jp.dissolve(|p| {
+    ///     let ward: u32 = p.code[..5].parse().ok()?;
+    ///     (13101..=13123).contains(&ward).then(|| Properties {
+    ///         code: "tokyo-23".into(),
+    ///         name: "東京23区".to_string(),
+    ///         en_name: "Tokyo 23 wards".to_string(),
+    ///         kana: None,
+    ///     })
+    /// });
+    /// assert_eq!(wards.features.len(), 1);
+    /// # }
+    /// ```
+    pub fn dissolve<F>(&self, key: F) -> FeatureCollection
+    where
+        F: Fn(&Properties) -> Option<Properties>,
+    {
+        let mut groups: Vec<(Properties, Vec<usize>)> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let features = self.features();
+        let mut members: Vec<&Feature> = Vec::new();
+        for feature in features
+            .iter()
+            .filter(|f| f.properties.code.level() == Some(AreaLevel::Class20))
+        {
+            let Some(group) = key(&feature.properties) else {
+                continue;
+            };
+            let idx = *index.entry(group.code.to_string()).or_insert_with(|| {
+                groups.push((group, Vec::new()));
+                groups.len() - 1
+            });
+            groups[idx].1.push(members.len());
+            members.push(feature);
+        }
+
+        let members: Vec<Feature> = members.into_iter().cloned().collect();
+        let topology = Topology::build(&members);
+
+        let features = groups
+            .into_iter()
+            .map(|(properties, feature_indexes)| {
+                // Arcs used once in the group are on its outline
+                let mut count: HashMap<usize, usize> = HashMap::new();
+                for &f in &feature_indexes {
+                    for arc_ref in topology.rings[f].iter().flatten().flatten() {
+                        *count.entry(arc_ref.index).or_default() += 1;
+                    }
+                }
+                let mut outline: Vec<usize> = count
+                    .into_iter()
+                    .filter(|&(_, n)| n % 2 == 1)
+                    .map(|(arc, _)| arc)
+                    .collect();
+                outline.sort_unstable();
+                let arcs: Vec<&Vec<[f64; 2]>> =
+                    outline.iter().map(|&i| &topology.arcs[i]).collect();
+
+                Feature {
+                    feature_type: "Feature".to_string(),
+                    geometry: Geometry {
+                        geometry_type: "MultiPolygon".to_string(),
+                        coordinates: assemble(stitch(&arcs)),
+                    },
+                    properties,
+                }
+            })
+            .collect();
+
+        FeatureCollection {
+            feature_type: "FeatureCollection".to_string(),
+            features,
+        }
+    }
+
+    /// Dissolve municipalities into groups and load the result as a new layer
+    ///
+    /// The groups are then returned by lookups, exports and metrics like any
+    /// other region. See [`ReverseJp::dissolve`].
+    pub fn add_dissolved_layer<F>(&mut self, key: F) -> Result<(), Box<dyn Error>>
+    where
+        F: Fn(&Properties) -> Option<Properties>,
+    {
+        let layer = self.next_layer().max(SOURCE_LAYER + 1);
+        let regions = self.dissolve(key);
+        self.load_layer(regions, layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::feature;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<[f64; 2]> {
+        vec![
+            [x, y],
+            [x + size, y],
+            [x + size, y + size],
+            [x, y + size],
+            [x, y],
+        ]
+    }
+
+    #[test]
+    fn test_dissolve_with_hole() {
+        // A 3x3 grid of municipalities whose center belongs to another group
+        let mut features = Vec::new();
+        for i in 0..9 {
+            let (x, y) = ((i % 3) as f64, (i / 3) as f64);
+            features.push(feature(&format!("000000{}", i), vec![square(x, y, 1.0)]));
+        }
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp
+            .process_feature_collection(FeatureCollection {
+                feature_type: "FeatureCollection".to_string(),
+                features,
+            })
+            .unwrap();
+
+        let dissolved = reverse_jp.dissolve(|p| {
+            let group = if p.code == "0000004" {
+                "center"
+            } else {
+                "ring"
+            };
+            Some(Properties {
+                code: group.into(),
+                name: group.to_string(),
+                en_name: String::new(),
+                kana: None,
+            })
+        });
+
+        assert_eq!(dissolved.features.len(), 2);
+        let ring = &dissolved.features[0];
+        assert_eq!(ring.properties.code, "ring");
+        assert_eq!(ring.geometry.coordinates.len(), 1);
+        assert_eq!(ring.geometry.coordinates[0].len(), 2);
+        assert!(signed_area(&ring.geometry.coordinates[0][0]) > 0.0);
+        assert!((signed_area(&ring.geometry.coordinates[0][0]) - 9.0).abs() < 1e-9);
+        assert!((signed_area(&ring.geometry.coordinates[0][1]) + 1.0).abs() < 1e-9);
+
+        reverse_jp.process_feature_collection(dissolved).unwrap();
+        let codes: Vec<String> = reverse_jp
+            .find_properties(1.5, 1.5)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert_eq!(codes, vec!["0000004", "center"]);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_dissolve_by_prefecture_and_office() {
+        let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let area = AreaData::with_embedded_data().unwrap();
+
+        let prefectures = reverse_jp.dissolve(by_prefecture);
+        assert_eq!(prefectures.features.len(), 47);
+        let tokyo = prefectures
+            .features
+            .iter()
+            .find(|f| f.properties.code == "13")
+            .unwrap();
+        assert_eq!(tokyo.properties.name, "東京都");
+
+        let offices = reverse_jp.dissolve(by_office(&area));
+        let tokyo = offices
+            .features
+            .iter()
+            .find(|f| f.properties.code == "130000")
+            .unwrap();
+        assert_eq!(tokyo.properties.name, "東京都");
+
+        // An office missing from the hierarchy leaves its municipalities out
+        let mut partial = area.clone();
+        partial.offices.remove("130000");
+        let chiyoda = reverse_jp.find_properties(139.7528, 35.6852);
+        let chiyoda = chiyoda.iter().find(|p| p.code == "1310100").unwrap();
+        assert_eq!(by_office(&partial)(chiyoda), None);
+        assert!(
+            reverse_jp
+                .dissolve(by_office(&partial))
+                .features
+                .iter()
+                .all(|f| f.properties.code != "130000")
+        );
+
+        reverse_jp.add_dissolved_layer(by_office(&area)).unwrap();
+        let codes: Vec<String> = reverse_jp
+            .find_properties(139.7528, 35.6852)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert!(codes.contains(&"130000".to_string()));
+        assert!(codes.contains(&"1310100".to_string()));
+
+        // Dissolving keeps the total area of the members, up to slivers in the data
+        let members: f64 = reverse_jp
+            .feature_collection(|p| p.code.starts_with("13") && p.code.len() == 7)
+            .features
+            .iter()
+            .map(|f| crate::metrics::measure(&f.geometry).unwrap().area_km2)
+            .sum();
+        let dissolved = reverse_jp.metrics("130000").unwrap().area_km2;
+        assert!(
+            (dissolved - members).abs() / members < 1e-4,
+            "{} {}",
+            dissolved,
+            members
+        );
+    }
+}
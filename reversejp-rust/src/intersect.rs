@@ -0,0 +1,130 @@
+// Polygon/polygon intersection tests used by region queries
+
+use geometry_rs::{Point, Polygon, Rect};
+
+fn rings(polygon: &Polygon) -> impl Iterator<Item = &[Point]> {
+    std::iter::once(polygon.exterior()).chain(polygon.holes().iter().map(|h| h.as_slice()))
+}
+
+fn rects_intersect(a: &Rect, b: &Rect) -> bool {
+    a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+}
+
+fn orientation(a: Point, b: Point, c: Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn on_segment(a: Point, b: Point, p: Point) -> bool {
+    p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+fn segments_intersect(a1: Point, a2: Point, b1: Point, b2: Point) -> bool {
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && on_segment(b1, b2, a1))
+        || (d2 == 0.0 && on_segment(b1, b2, a2))
+        || (d3 == 0.0 && on_segment(a1, a2, b1))
+        || (d4 == 0.0 && on_segment(a1, a2, b2))
+}
+
+fn segment_rect(a: Point, b: Point) -> Rect {
+    Rect {
+        min: Point {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+        },
+        max: Point {
+            x: a.x.max(b.x),
+            y: a.y.max(b.y),
+        },
+    }
+}
+
+/// Whether two polygons (holes included) share at least one point
+pub(crate) fn polygons_intersect(a: &Polygon, b: &Polygon) -> bool {
+    let (a_rect, b_rect) = (a.rect(), b.rect());
+    if !rects_intersect(&a_rect, &b_rect) {
+        return false;
+    }
+
+    // One polygon lies inside the other
+    if let Some(&p) = a.exterior().first()
+        && b.contains_point(p)
+    {
+        return true;
+    }
+    if let Some(&p) = b.exterior().first()
+        && a.contains_point(p)
+    {
+        return true;
+    }
+
+    // Otherwise their boundaries must cross
+    let b_segments: Vec<(Point, Point, Rect)> = rings(b)
+        .flat_map(|ring| ring.windows(2))
+        .map(|w| (w[0], w[1], segment_rect(w[0], w[1])))
+        .filter(|(_, _, rect)| rects_intersect(rect, &a_rect))
+        .collect();
+
+    for ring in rings(a) {
+        for w in ring.windows(2) {
+            let rect = segment_rect(w[0], w[1]);
+            if !rects_intersect(&rect, &b_rect) {
+                continue;
+            }
+            for (b1, b2, b_rect) in &b_segments {
+                if rects_intersect(&rect, b_rect) && segments_intersect(w[0], w[1], *b1, *b2) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point> {
+        vec![
+            Point { x, y },
+            Point { x: x + size, y },
+            Point {
+                x: x + size,
+                y: y + size,
+            },
+            Point { x, y: y + size },
+            Point { x, y },
+        ]
+    }
+
+    #[test]
+    fn test_polygons_intersect() {
+        let big = Polygon::new(square(0.0, 0.0, 10.0), vec![], None);
+        let holed = Polygon::new(square(0.0, 0.0, 10.0), vec![square(2.0, 2.0, 6.0)], None);
+
+        let overlapping = Polygon::new(square(8.0, 8.0, 4.0), vec![], None);
+        let inside = Polygon::new(square(3.0, 3.0, 1.0), vec![], None);
+        let outside = Polygon::new(square(20.0, 20.0, 1.0), vec![], None);
+        let touching = Polygon::new(square(10.0, 0.0, 1.0), vec![], None);
+
+        assert!(polygons_intersect(&big, &overlapping));
+        assert!(polygons_intersect(&big, &inside));
+        assert!(polygons_intersect(&inside, &big));
+        assert!(polygons_intersect(&big, &touching));
+        assert!(!polygons_intersect(&big, &outside));
+        assert!(!polygons_intersect(&holed, &inside));
+        assert!(polygons_intersect(&holed, &overlapping));
+    }
+}
@@ -0,0 +1,378 @@
+//! Kana and romaji conversions used to normalise region names.
+//!
+//! ```rust
+//! use reversejp::kana;
+//!
+//! assert_eq!(kana::to_hepburn("とうきょう"), "tokyo");
+//! assert_eq!(kana::from_romaji("sibuya").as_deref(), Some("しぶや"));
+//! assert_eq!(kana::to_hiragana(&kana::fold_width("ｼﾌﾞﾔ")), "しぶや");
+//! ```
+
+/// Half-width katakana from U+FF61 to U+FF9F, in code point order
+const HALF_WIDTH_KANA: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Romaji syllables accepted by [`from_romaji`], in Hepburn and Kunrei-shiki
+#[rustfmt::skip]
+const ROMAJI: &[(&str, &str)] = &[
+    ("a", "あ"), ("i", "い"), ("u", "う"), ("e", "え"), ("o", "お"),
+    ("ka", "か"), ("ki", "き"), ("ku", "く"), ("ke", "け"), ("ko", "こ"),
+    ("kya", "きゃ"), ("kyu", "きゅ"), ("kyo", "きょ"),
+    ("ga", "が"), ("gi", "ぎ"), ("gu", "ぐ"), ("ge", "げ"), ("go", "ご"),
+    ("gya", "ぎゃ"), ("gyu", "ぎゅ"), ("gyo", "ぎょ"),
+    ("sa", "さ"), ("si", "し"), ("shi", "し"), ("su", "す"), ("se", "せ"), ("so", "そ"),
+    ("sya", "しゃ"), ("syu", "しゅ"), ("syo", "しょ"),
+    ("sha", "しゃ"), ("shu", "しゅ"), ("sho", "しょ"), ("she", "しぇ"),
+    ("za", "ざ"), ("zi", "じ"), ("ji", "じ"), ("zu", "ず"), ("ze", "ぜ"), ("zo", "ぞ"),
+    ("zya", "じゃ"), ("zyu", "じゅ"), ("zyo", "じょ"),
+    ("ja", "じゃ"), ("ju", "じゅ"), ("jo", "じょ"), ("je", "じぇ"),
+    ("jya", "じゃ"), ("jyu", "じゅ"), ("jyo", "じょ"),
+    ("ta", "た"), ("ti", "ち"), ("chi", "ち"), ("tu", "つ"), ("tsu", "つ"), ("te", "て"), ("to", "と"),
+    ("tya", "ちゃ"), ("tyu", "ちゅ"), ("tyo", "ちょ"),
+    ("cha", "ちゃ"), ("chu", "ちゅ"), ("cho", "ちょ"), ("che", "ちぇ"),
+    ("cya", "ちゃ"), ("cyu", "ちゅ"), ("cyo", "ちょ"),
+    ("da", "だ"), ("di", "ぢ"), ("du", "づ"), ("de", "で"), ("do", "ど"),
+    ("dya", "ぢゃ"), ("dyu", "ぢゅ"), ("dyo", "ぢょ"),
+    ("na", "な"), ("ni", "に"), ("nu", "ぬ"), ("ne", "ね"), ("no", "の"),
+    ("nya", "にゃ"), ("nyu", "にゅ"), ("nyo", "にょ"),
+    ("ha", "は"), ("hi", "ひ"), ("hu", "ふ"), ("fu", "ふ"), ("he", "へ"), ("ho", "ほ"),
+    ("hya", "ひゃ"), ("hyu", "ひゅ"), ("hyo", "ひょ"),
+    ("fa", "ふぁ"), ("fi", "ふぃ"), ("fe", "ふぇ"), ("fo", "ふぉ"),
+    ("ba", "ば"), ("bi", "び"), ("bu", "ぶ"), ("be", "べ"), ("bo", "ぼ"),
+    ("bya", "びゃ"), ("byu", "びゅ"), ("byo", "びょ"),
+    ("pa", "ぱ"), ("pi", "ぴ"), ("pu", "ぷ"), ("pe", "ぺ"), ("po", "ぽ"),
+    ("pya", "ぴゃ"), ("pyu", "ぴゅ"), ("pyo", "ぴょ"),
+    ("ma", "ま"), ("mi", "み"), ("mu", "む"), ("me", "め"), ("mo", "も"),
+    ("mya", "みゃ"), ("myu", "みゅ"), ("myo", "みょ"),
+    ("ya", "や"), ("yu", "ゆ"), ("yo", "よ"),
+    ("ra", "ら"), ("ri", "り"), ("ru", "る"), ("re", "れ"), ("ro", "ろ"),
+    ("rya", "りゃ"), ("ryu", "りゅ"), ("ryo", "りょ"),
+    ("wa", "わ"), ("wo", "を"), ("vu", "ゔ"),
+];
+
+/// Hepburn romanisation of single hiragana
+fn hepburn_syllable(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' | 'ぁ' => "a",
+        'い' | 'ぃ' | 'ゐ' => "i",
+        'う' | 'ぅ' => "u",
+        'え' | 'ぇ' | 'ゑ' => "e",
+        'お' | 'ぉ' | 'を' => "o",
+        'か' | 'ゕ' => "ka",
+        'き' => "ki",
+        'く' => "ku",
+        'け' | 'ゖ' => "ke",
+        'こ' => "ko",
+        'が' => "ga",
+        'ぎ' => "gi",
+        'ぐ' => "gu",
+        'げ' => "ge",
+        'ご' => "go",
+        'さ' => "sa",
+        'し' => "shi",
+        'す' => "su",
+        'せ' => "se",
+        'そ' => "so",
+        'ざ' => "za",
+        'じ' | 'ぢ' => "ji",
+        'ず' | 'づ' => "zu",
+        'ぜ' => "ze",
+        'ぞ' => "zo",
+        'た' => "ta",
+        'ち' => "chi",
+        'つ' => "tsu",
+        'て' => "te",
+        'と' => "to",
+        'だ' => "da",
+        'で' => "de",
+        'ど' => "do",
+        'な' => "na",
+        'に' => "ni",
+        'ぬ' => "nu",
+        'ね' => "ne",
+        'の' => "no",
+        'は' => "ha",
+        'ひ' => "hi",
+        'ふ' => "fu",
+        'へ' => "he",
+        'ほ' => "ho",
+        'ば' => "ba",
+        'び' => "bi",
+        'ぶ' => "bu",
+        'べ' => "be",
+        'ぼ' => "bo",
+        'ぱ' => "pa",
+        'ぴ' => "pi",
+        'ぷ' => "pu",
+        'ぺ' => "pe",
+        'ぽ' => "po",
+        'ま' => "ma",
+        'み' => "mi",
+        'む' => "mu",
+        'め' => "me",
+        'も' => "mo",
+        'や' | 'ゃ' => "ya",
+        'ゆ' | 'ゅ' => "yu",
+        'よ' | 'ょ' => "yo",
+        'ら' => "ra",
+        'り' => "ri",
+        'る' => "ru",
+        'れ' => "re",
+        'ろ' => "ro",
+        'わ' | 'ゎ' => "wa",
+        'ゔ' => "vu",
+        _ => return None,
+    })
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+/// Fold full-width ASCII to ASCII and half-width katakana to full-width
+pub fn fold_width(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\u{FF01}'..='\u{FF5E}' => {
+                out.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c));
+            }
+            '\u{3000}' => out.push(' '),
+            '\u{FF61}'..='\u{FF9F}' => {
+                let kana = HALF_WIDTH_KANA
+                    .chars()
+                    .nth((c as u32 - 0xFF61) as usize)
+                    .unwrap_or(c);
+                // Combine voiced sound marks with the preceding kana
+                let combined = match (kana, out.chars().last()) {
+                    ('゛', Some('ウ')) => Some('ヴ'),
+                    ('゛', Some(p @ ('カ'..='ト' | 'ハ'..='ホ'))) => {
+                        char::from_u32(p as u32 + 1)
+                    }
+                    ('゜', Some(p @ 'ハ'..='ホ')) => char::from_u32(p as u32 + 2),
+                    _ => None,
+                };
+                match combined {
+                    Some(combined) => {
+                        out.pop();
+                        out.push(combined);
+                    }
+                    None => out.push(kana),
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Convert katakana to hiragana, leaving other characters untouched
+pub fn to_hiragana(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Convert hiragana to katakana, leaving other characters untouched
+pub fn to_katakana(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 + 0x60).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Romanise kana with the Hepburn system, without macrons
+///
+/// Long vowels are written as a single vowel, as in place names (`とうきょう`
+/// becomes `tokyo`, `おおいた` becomes `oita`). The result is lowercase;
+/// characters other than kana are kept as they are, except `・` which
+/// becomes a space.
+pub fn to_hepburn(kana: &str) -> String {
+    let chars: Vec<char> = to_hiragana(kana).chars().collect();
+    let mut syllables: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let Some(romaji) = hepburn_syllable(c) else {
+            syllables.push(match c {
+                '・' => " ".to_string(),
+                'ー' => String::new(),
+                _ => c.to_string(),
+            });
+            continue;
+        };
+        let mut romaji = romaji.to_string();
+        // Contracted sounds: きゃ kya, しゃ sha, ふぁ fa, てぃ ti
+        match chars.get(i) {
+            Some(&small @ ('ゃ' | 'ゅ' | 'ょ')) if romaji.ends_with('i') && romaji.len() > 1 => {
+                romaji.pop();
+                if !romaji.ends_with("sh") && !romaji.ends_with("ch") && !romaji.ends_with('j') {
+                    romaji.push('y');
+                }
+                romaji.push_str(&hepburn_syllable(small).unwrap_or_default()[1..]);
+                i += 1;
+            }
+            Some(&small @ ('ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ')) => {
+                if romaji == "u" {
+                    romaji = "w".to_string();
+                } else {
+                    romaji.pop();
+                }
+                romaji.push_str(hepburn_syllable(small).unwrap_or_default());
+                i += 1;
+            }
+            _ => {}
+        }
+        // Long vowels: おう, おお and うう are written as a single vowel
+        while matches!(
+            (romaji.chars().last(), chars.get(i)),
+            (Some('o'), Some('う' | 'お')) | (Some('u'), Some('う'))
+        ) && !matches!(chars.get(i + 1), Some('ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ'))
+        {
+            i += 1;
+        }
+        syllables.push(romaji);
+    }
+
+    let mut out = String::new();
+    for (idx, syllable) in syllables.iter().enumerate() {
+        let next = syllables[idx + 1..]
+            .iter()
+            .find(|s| !s.is_empty())
+            .map(String::as_str)
+            .unwrap_or("");
+        match syllable.as_str() {
+            "っ" => {
+                if next.starts_with("ch") {
+                    out.push('t');
+                } else if let Some(c) = next
+                    .chars()
+                    .next()
+                    .filter(|c| c.is_ascii_alphabetic() && !is_vowel(*c))
+                {
+                    out.push(c);
+                }
+            }
+            "ん" => {
+                out.push('n');
+                if next.starts_with(|c: char| is_vowel(c) || c == 'y') {
+                    out.push('\'');
+                }
+            }
+            _ => out.push_str(syllable),
+        }
+    }
+    out
+}
+
+/// Convert romaji in Hepburn or Kunrei-shiki to hiragana
+///
+/// Returns `None` if the input is not entirely romaji. Case, hyphens and
+/// spaces are ignored; an apostrophe separates `n` from a following vowel.
+pub fn from_romaji(romaji: &str) -> Option<String> {
+    let chars: Vec<char> = romaji
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if chars.is_empty() || !chars.iter().all(|c| c.is_ascii_lowercase() || *c == '\'') {
+        return None;
+    }
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '\'' {
+            i += 1;
+            continue;
+        }
+        if c == 'n' && !next.is_some_and(|n| is_vowel(n) || n == 'y') {
+            // `nn` is a single ん unless a vowel follows
+            let double =
+                next == Some('n') && !chars.get(i + 2).is_some_and(|&n| is_vowel(n) || n == 'y');
+            out.push('ん');
+            i += if double { 2 } else { 1 };
+            continue;
+        }
+        if c == 'm' && matches!(next, Some('b' | 'm' | 'p')) {
+            out.push('ん');
+            i += 1;
+            continue;
+        }
+        if !is_vowel(c) && (next == Some(c) || (c == 't' && next == Some('c'))) {
+            out.push('っ');
+            i += 1;
+            continue;
+        }
+
+        let (len, kana) = (1..=3)
+            .rev()
+            .filter(|len| i + len <= chars.len())
+            .find_map(|len| {
+                let syllable: String = chars[i..i + len].iter().collect();
+                ROMAJI
+                    .iter()
+                    .find(|(romaji, _)| *romaji == syllable)
+                    .map(|(_, kana)| (len, *kana))
+            })?;
+        out.push_str(kana);
+        i += len;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_width() {
+        assert_eq!(fold_width("ＳＨＩＢＵＹＡ　１"), "SHIBUYA 1");
+        assert_eq!(fold_width("ｼﾌﾞﾔ"), "シブヤ");
+        assert_eq!(fold_width("ﾎﾟﾝﾄﾞ"), "ポンド");
+        assert_eq!(fold_width("渋谷区"), "渋谷区");
+    }
+
+    #[test]
+    fn test_kana_conversion() {
+        assert_eq!(to_hiragana("シブヤ区"), "しぶや区");
+        assert_eq!(to_katakana("しぶや区"), "シブヤ区");
+    }
+
+    #[test]
+    fn test_to_hepburn() {
+        assert_eq!(to_hepburn("しぶやく"), "shibuyaku");
+        assert_eq!(to_hepburn("とうきょう"), "tokyo");
+        assert_eq!(to_hepburn("おおさか"), "osaka");
+        assert_eq!(to_hepburn("さっぽろし"), "sapporoshi");
+        assert_eq!(to_hepburn("はっちょうぼり"), "hatchobori");
+        assert_eq!(to_hepburn("しんじゅく"), "shinjuku");
+        assert_eq!(to_hepburn("じゅんいち"), "jun'ichi");
+        assert_eq!(to_hepburn("ひょうご"), "hyogo");
+        assert_eq!(to_hepburn("ニイガタ"), "niigata");
+        assert_eq!(to_hepburn("ふぁ・てぃ"), "fa ti");
+    }
+
+    #[test]
+    fn test_from_romaji() {
+        assert_eq!(from_romaji("shibuya").as_deref(), Some("しぶや"));
+        assert_eq!(from_romaji("Sibuya").as_deref(), Some("しぶや"));
+        assert_eq!(from_romaji("sapporo").as_deref(), Some("さっぽろ"));
+        assert_eq!(from_romaji("shinjuku").as_deref(), Some("しんじゅく"));
+        assert_eq!(from_romaji("shimbashi").as_deref(), Some("しんばし"));
+        assert_eq!(from_romaji("konnichi").as_deref(), Some("こんにち"));
+        assert_eq!(from_romaji("jun'ichi").as_deref(), Some("じゅんいち"));
+        assert_eq!(from_romaji("hatchobori").as_deref(), Some("はっちょぼり"));
+        assert_eq!(from_romaji("shibuya-ku").as_deref(), Some("しぶやく"));
+        assert!(from_romaji("xyz").is_none());
+        assert!(from_romaji("渋谷").is_none());
+        assert!(from_romaji("").is_none());
+    }
+}
@@ -1,27 +1,71 @@
-#![doc = include_str!("../README.md")]
+// The README examples read the embedded data
+#![cfg_attr(feature = "embedded", doc = include_str!("../README.md"))]
 
-use std::collections::HashMap;
+mod adjacency;
+pub mod area;
+pub mod area_code;
+pub mod bosai;
+#[cfg(feature = "jma-xml")]
+pub mod bulletin;
+pub mod dissolve;
+mod intersect;
+pub mod kana;
+pub mod locale;
+pub mod metrics;
+pub mod mvt;
+pub mod prefecture;
+pub mod search;
+pub mod simplify;
+mod topology;
+pub mod warning;
+pub mod wkb;
+pub mod wkt;
+pub mod xy;
+
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::io::Read;
+use std::path::Path;
+use std::sync::OnceLock;
 use zip::read::ZipArchive;
 
+pub use area_code::{AreaCode, AreaLevel};
+
 use geometry_rs::{Point, Polygon};
 use serde::{Deserialize, Serialize};
 
 // Embedded ZIP files
+#[cfg(feature = "embedded")]
+const EMBEDDED_AREA_DATA: &[u8] = include_bytes!("../data/area.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_CLASS10S_DATA: &[u8] = include_bytes!("../data/class10s.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_0_DATA: &[u8] = include_bytes!("../data/landslides_0.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_1_DATA: &[u8] = include_bytes!("../data/landslides_1.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_2_DATA: &[u8] = include_bytes!("../data/landslides_2.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_3_DATA: &[u8] = include_bytes!("../data/landslides_3.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_4_DATA: &[u8] = include_bytes!("../data/landslides_4.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_5_DATA: &[u8] = include_bytes!("../data/landslides_5.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_6_DATA: &[u8] = include_bytes!("../data/landslides_6.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_7_DATA: &[u8] = include_bytes!("../data/landslides_7.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_8_DATA: &[u8] = include_bytes!("../data/landslides_8.json.zip");
+#[cfg(feature = "embedded")]
 const EMBEDDED_LANDSLIDES_9_DATA: &[u8] = include_bytes!("../data/landslides_9.json.zip");
+#[cfg(feature = "embedded")]
+const EMBEDDED_WARNING_DATA: &[u8] = include_bytes!("../data/warning.json.zip");
+#[cfg(feature = "embedded")]
+const EMBEDDED_XY_DATA: &[u8] = include_bytes!("../data/xy.json.zip");
 
 // Function to extract JSON from zip data
+#[cfg(feature = "embedded")]
 fn extract_json_from_zip(
     zip_data: &[u8],
     filename: &str,
@@ -35,12 +79,32 @@ fn extract_json_from_zip(
     Ok(contents)
 }
 
+// Helper function to get the area hierarchy data
+#[cfg(feature = "embedded")]
+pub fn get_area_data() -> Result<String, Box<dyn std::error::Error>> {
+    extract_json_from_zip(EMBEDDED_AREA_DATA, "area.json")
+}
+
+// Helper function to get the warning element list
+#[cfg(feature = "embedded")]
+pub fn get_warning_data() -> Result<String, Box<dyn std::error::Error>> {
+    extract_json_from_zip(EMBEDDED_WARNING_DATA, "warning.json")
+}
+
+// Helper function to get the representative points of areas
+#[cfg(feature = "embedded")]
+pub fn get_xy_data() -> Result<String, Box<dyn std::error::Error>> {
+    extract_json_from_zip(EMBEDDED_XY_DATA, "xy.json")
+}
+
 // Helper function to get class10s data
+#[cfg(feature = "embedded")]
 pub fn get_class10s_data() -> Result<String, Box<dyn std::error::Error>> {
     extract_json_from_zip(EMBEDDED_CLASS10S_DATA, "class10s.json")
 }
 
 // Helper function to get landslide data for a specific index
+#[cfg(feature = "embedded")]
 pub fn get_landslide_data(idx: usize) -> Result<String, Box<dyn std::error::Error>> {
     let zip_data: &[u8] = match idx {
         0 => EMBEDDED_LANDSLIDES_0_DATA,
@@ -59,15 +123,33 @@ pub fn get_landslide_data(idx: usize) -> Result<String, Box<dyn std::error::Erro
     extract_json_from_zip(zip_data, &format!("landslides_{}.json", idx))
 }
 
+// Convert a GeoJSON linear ring to geometry-rs points
+fn ring_to_points(ring: &[[f64; 2]]) -> Vec<Point> {
+    ring.iter()
+        .map(|coord| Point {
+            x: coord[0],
+            y: coord[1],
+        })
+        .collect()
+}
+
+// GeoJSON coordinates of a loaded polygon, the exterior ring first
+fn polygon_coordinates(polygon: &Polygon) -> Vec<Vec<[f64; 2]>> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.holes().iter().map(Vec::as_slice))
+        .map(|ring| ring.iter().map(|point| [point.x, point.y]).collect())
+        .collect()
+}
+
 // GeoJSON types for deserialization
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FeatureCollection {
     #[serde(rename = "type")]
     pub feature_type: String,
     pub features: Vec<Feature>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Feature {
     #[serde(rename = "type")]
     pub feature_type: String,
@@ -75,24 +157,36 @@ pub struct Feature {
     pub properties: Properties,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Geometry {
     #[serde(rename = "type")]
     pub geometry_type: String,
     pub coordinates: Vec<Vec<Vec<[f64; 2]>>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Properties {
-    pub code: String,
+    pub code: AreaCode,
     pub name: String,
     #[serde(rename = "enName", default)]
     pub en_name: String,
+    /// Reading of the name in hiragana, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kana: Option<String>,
 }
 
+// Layer of the regions loaded from data, as opposed to dissolved layers
+pub(crate) const SOURCE_LAYER: u32 = 0;
+
 // Main struct for reverse geocoding
 pub struct ReverseJp {
     polygons: Vec<(Polygon, Properties)>,
+    // Layer of each polygon, SOURCE_LAYER for loaded data then one per dissolved layer
+    layers: Vec<u32>,
+    // Neighbouring regions by code, computed on first use
+    adjacency: OnceLock<HashMap<String, Vec<Properties>>>,
+    // Position in `regions()` of the region of each polygon, computed on first use
+    region_index: OnceLock<Vec<usize>>,
 }
 
 impl Default for ReverseJp {
@@ -106,6 +200,9 @@ impl ReverseJp {
     pub fn new() -> Self {
         ReverseJp {
             polygons: Vec::new(),
+            layers: Vec::new(),
+            adjacency: OnceLock::new(),
+            region_index: OnceLock::new(),
         }
     }
 
@@ -113,53 +210,135 @@ impl ReverseJp {
     ///
     /// This is the recommended way to use the library as it doesn't require
     /// downloading and managing external data files.
+    #[cfg(feature = "embedded")]
     pub fn with_embedded_data() -> Result<Self, Box<dyn Error>> {
         let mut reverse_jp = Self::new();
 
         // Load embedded data
-        reverse_jp.load_from_str(get_class10s_data()?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(0)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(1)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(2)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(3)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(4)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(5)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(6)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(7)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(8)?.as_str())?;
-        reverse_jp.load_from_str(get_landslide_data(9)?.as_str())?;
+        reverse_jp.load_geojson(get_class10s_data()?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(0)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(1)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(2)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(3)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(4)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(5)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(6)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(7)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(8)?.as_str())?;
+        reverse_jp.load_geojson(get_landslide_data(9)?.as_str())?;
 
         Ok(reverse_jp)
     }
 
-    /// Load data from a GeoJSON string
-    fn load_from_str(&mut self, json_str: &str) -> Result<(), Box<dyn Error>> {
+    /// Add a custom region to the lookup data
+    ///
+    /// The geometry must be a MultiPolygon, as produced by [`wkt::from_wkt`] and
+    /// [`wkb::from_wkb`]. The region is then returned by lookups and geometry
+    /// exports like any embedded region.
+    pub fn add_region(
+        &mut self,
+        geometry: Geometry,
+        properties: Properties,
+    ) -> Result<(), Box<dyn Error>> {
+        if geometry.geometry_type != "MultiPolygon" {
+            return Err(format!("Unsupported geometry type: {}", geometry.geometry_type).into());
+        }
+
+        self.process_feature_collection(FeatureCollection {
+            feature_type: "FeatureCollection".to_string(),
+            features: vec![Feature {
+                feature_type: "Feature".to_string(),
+                geometry,
+                properties,
+            }],
+        })
+    }
+
+    /// Add a custom region from a WKT `POLYGON` or `MULTIPOLYGON`
+    pub fn load_wkt(&mut self, wkt: &str, properties: Properties) -> Result<(), Box<dyn Error>> {
+        self.add_region(wkt::from_wkt(wkt)?, properties)
+    }
+
+    /// Add a custom region from a WKB `Polygon` or `MultiPolygon`
+    pub fn load_wkb(&mut self, wkb: &[u8], properties: Properties) -> Result<(), Box<dyn Error>> {
+        self.add_region(wkb::from_wkb(wkb)?, properties)
+    }
+
+    /// Load regions from a GeoJSON `FeatureCollection` string
+    ///
+    /// Features without a `MultiPolygon` geometry are ignored. Properties must
+    /// have a `code` and a `name`; `enName` and `kana` are optional.
+    ///
+    /// As in GeoJSON, the first ring of each polygon is its exterior and the
+    /// others are holes: points inside a hole are not part of the region.
+    pub fn load_geojson(&mut self, json_str: &str) -> Result<(), Box<dyn Error>> {
         let feature_collection: FeatureCollection = serde_json::from_str(json_str)?;
         self.process_feature_collection(feature_collection)
     }
 
+    /// Load regions from a GeoJSON `FeatureCollection` file
+    ///
+    /// See [`ReverseJp::load_geojson`].
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        self.load_geojson(&std::fs::read_to_string(path)?)
+    }
+
+    /// Load regions from GeoJSON bytes, or a ZIP archive of GeoJSON files
+    ///
+    /// Archives like the embedded `*.json.zip` files are detected by their
+    /// signature, and every `.json` entry is loaded. Anything else is parsed as
+    /// a UTF-8 GeoJSON `FeatureCollection`, see [`ReverseJp::load_geojson`].
+    pub fn load_bytes(&mut self, data: &[u8]) -> Result<(), Box<dyn Error>> {
+        if !data.starts_with(b"PK\x03\x04") {
+            let feature_collection: FeatureCollection = serde_json::from_slice(data)?;
+            return self.process_feature_collection(feature_collection);
+        }
+
+        let mut archive = ZipArchive::new(std::io::Cursor::new(data))?;
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx)?;
+            if !file.is_file() || !file.name().ends_with(".json") {
+                continue;
+            }
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            self.load_geojson(&contents)?;
+        }
+        Ok(())
+    }
+
     // Process a feature collection by converting GeoJSON to polygons
     fn process_feature_collection(
         &mut self,
         feature_collection: FeatureCollection,
     ) -> Result<(), Box<dyn Error>> {
+        self.load_layer(feature_collection, SOURCE_LAYER)
+    }
+
+    // Load the polygons of a feature collection into the given layer
+    pub(crate) fn load_layer(
+        &mut self,
+        feature_collection: FeatureCollection,
+        layer: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        // New regions may border the loaded ones
+        self.adjacency = OnceLock::new();
+        self.region_index = OnceLock::new();
+
         for feature in feature_collection.features {
             if feature.geometry.geometry_type == "MultiPolygon" {
-                for multi_polygon in &feature.geometry.coordinates {
-                    for polygon_coords in multi_polygon {
-                        // Convert GeoJSON coordinates to geometry-rs points
-                        let points: Vec<Point> = polygon_coords
-                            .iter()
-                            .map(|coord| Point {
-                                x: coord[0],
-                                y: coord[1],
-                            })
-                            .collect();
-
-                        // Create geometry-rs polygon
-                        let polygon = Polygon::new(points, vec![], None);
-                        self.polygons.push((polygon, feature.properties.clone()));
-                    }
+                for polygon_coords in &feature.geometry.coordinates {
+                    // The first ring is the exterior, the remaining rings are holes
+                    let mut rings = polygon_coords.iter().map(|ring| ring_to_points(ring));
+                    let Some(exterior) = rings.next().filter(|ring| !ring.is_empty()) else {
+                        continue;
+                    };
+                    let holes: Vec<Vec<Point>> = rings.collect();
+
+                    // Create geometry-rs polygon
+                    let polygon = Polygon::new(exterior, holes, None);
+                    self.polygons.push((polygon, feature.properties.clone()));
+                    self.layers.push(layer);
                 }
             }
         }
@@ -180,6 +359,41 @@ impl ReverseJp {
     ///
     /// A vector of Properties for all regions containing the point
     pub fn find_properties(&self, longitude: f64, latitude: f64) -> Vec<Properties> {
+        self.find_properties_ref(longitude, latitude)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Find all properties for a given longitude/latitude coordinate, without copying them
+    ///
+    /// The same as [`ReverseJp::find_properties`], for batch lookups that only
+    /// need to identify the regions.
+    pub fn find_properties_ref(&self, longitude: f64, latitude: f64) -> Vec<&Properties> {
+        self.find_polygons(longitude, latitude)
+            .into_iter()
+            .map(|idx| &self.polygons[idx].1)
+            .collect()
+    }
+
+    /// Find the regions containing a longitude/latitude coordinate, as positions in [`ReverseJp::regions`]
+    ///
+    /// Unlike codes, positions tell apart regions loaded with the same code, e.g.
+    /// custom data over the embedded data.
+    pub fn find_region_indices(&self, longitude: f64, latitude: f64) -> Vec<usize> {
+        let index = self.region_index();
+        let mut regions: Vec<usize> = Vec::new();
+        for idx in self.find_polygons(longitude, latitude) {
+            if !regions.contains(&index[idx]) {
+                regions.push(index[idx]);
+            }
+        }
+        regions
+    }
+
+    // Indices of the polygons containing a point, retried with small shifts
+    // when the point falls between polygons
+    fn find_polygons(&self, longitude: f64, latitude: f64) -> Vec<usize> {
         for lng_shift in [0.0, 0.001, -0.001, 0.002, -0.002, 0.005, -0.005] {
             for lat_shift in [0.0, 0.001, -0.001, 0.002, -0.002, 0.005, -0.005] {
                 let point = Point {
@@ -188,14 +402,15 @@ impl ReverseJp {
                 };
 
                 // Find all polygons that contain the point
-                let properties: Vec<Properties> = self
+                let polygons: Vec<usize> = self
                     .polygons
                     .iter()
-                    .filter(|(polygon, _)| polygon.contains_point(point))
-                    .map(|(_, props)| props.clone())
+                    .enumerate()
+                    .filter(|(_, (polygon, _))| polygon.contains_point(point))
+                    .map(|(idx, _)| idx)
                     .collect();
-                if !properties.is_empty() {
-                    return properties;
+                if !polygons.is_empty() {
+                    return polygons;
                 }
             }
         }
@@ -224,11 +439,234 @@ impl ReverseJp {
         let mut map = HashMap::new();
 
         for props in results {
-            map.insert(props.code.clone(), props);
+            map.insert(props.code.to_string(), props);
         }
 
         map
     }
+
+    /// Get the geometry of a region by its code
+    ///
+    /// A region may be split across several features in the source data, all of
+    /// their polygons are merged into a single MultiPolygon, holes included.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The region code, e.g. `130010` or `1310100`
+    ///
+    /// # Returns
+    ///
+    /// The MultiPolygon geometry of the region, or `None` if the code is not loaded
+    pub fn geometry(&self, code: &str) -> Option<Geometry> {
+        let coordinates: Vec<_> = self
+            .polygons
+            .iter()
+            .filter(|(_, props)| props.code == code)
+            .map(|(polygon, _)| polygon_coordinates(polygon))
+            .collect();
+
+        if coordinates.is_empty() {
+            return None;
+        }
+
+        Some(Geometry {
+            geometry_type: "MultiPolygon".to_string(),
+            coordinates,
+        })
+    }
+
+    /// The properties of each loaded region, in load order
+    ///
+    /// Polygons sharing a code and a name make up one region, so regions loaded
+    /// with the same code under another name are listed separately.
+    pub fn regions(&self) -> Vec<&Properties> {
+        let mut regions = Vec::new();
+        for ((_, props), &idx) in self.polygons.iter().zip(self.region_index()) {
+            if idx == regions.len() {
+                regions.push(props);
+            }
+        }
+        regions
+    }
+
+    fn region_index(&self) -> &[usize] {
+        self.region_index.get_or_init(|| {
+            let mut seen: HashMap<(&str, &str), usize> = HashMap::new();
+            self.polygons
+                .iter()
+                .map(|(_, props)| {
+                    let next = seen.len();
+                    *seen
+                        .entry((props.code.as_str(), props.name.as_str()))
+                        .or_insert(next)
+                })
+                .collect()
+        })
+    }
+
+    // The loaded polygons as features with their layer, consecutive polygons of a
+    // region sharing one
+    //
+    // Rebuilt on demand from the lookup polygons, so coordinates are only held once.
+    pub(crate) fn layered_features(&self) -> Vec<(u32, Feature)> {
+        let mut features: Vec<(u32, Feature)> = Vec::new();
+        for ((polygon, props), &layer) in self.polygons.iter().zip(&self.layers) {
+            let coordinates = polygon_coordinates(polygon);
+            match features.last_mut() {
+                Some((last, feature)) if *last == layer && feature.properties == *props => {
+                    feature.geometry.coordinates.push(coordinates)
+                }
+                _ => features.push((
+                    layer,
+                    Feature {
+                        feature_type: "Feature".to_string(),
+                        geometry: Geometry {
+                            geometry_type: "MultiPolygon".to_string(),
+                            coordinates: vec![coordinates],
+                        },
+                        properties: props.clone(),
+                    },
+                )),
+            }
+        }
+        features
+    }
+
+    // The loaded polygons as features, see `layered_features`
+    pub(crate) fn features(&self) -> Vec<Feature> {
+        self.layered_features()
+            .into_iter()
+            .map(|(_, feature)| feature)
+            .collect()
+    }
+
+    // A layer id not used yet, for a new dissolved layer
+    pub(crate) fn next_layer(&self) -> u32 {
+        self.layers
+            .iter()
+            .max()
+            .map_or(SOURCE_LAYER, |layer| layer + 1)
+    }
+
+    /// Collect the regions matching `filter` into a GeoJSON FeatureCollection
+    ///
+    /// Each region appears once, with its polygons merged into a single
+    /// MultiPolygon and its full properties attached. Regions are ordered as
+    /// they were loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Predicate selecting the regions to export
+    ///
+    /// # Returns
+    ///
+    /// A FeatureCollection with one feature per matching region code
+    pub fn feature_collection<F>(&self, filter: F) -> FeatureCollection
+    where
+        F: Fn(&Properties) -> bool,
+    {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut features: Vec<Feature> = Vec::new();
+
+        for (polygon, props) in self.polygons.iter().filter(|(_, props)| filter(props)) {
+            let coordinates = polygon_coordinates(polygon);
+            match index.get(props.code.as_str()) {
+                Some(&idx) => features[idx].geometry.coordinates.push(coordinates),
+                None => {
+                    index.insert(props.code.as_str(), features.len());
+                    features.push(Feature {
+                        feature_type: "Feature".to_string(),
+                        geometry: Geometry {
+                            geometry_type: "MultiPolygon".to_string(),
+                            coordinates: vec![coordinates],
+                        },
+                        properties: props.clone(),
+                    });
+                }
+            }
+        }
+
+        FeatureCollection {
+            feature_type: "FeatureCollection".to_string(),
+            features,
+        }
+    }
+
+    /// Get the geometry of a region by its code as WKT
+    pub fn geometry_wkt(&self, code: &str) -> Option<String> {
+        self.geometry(code).map(|geometry| wkt::to_wkt(&geometry))
+    }
+
+    /// Get the geometry of a region by its code as little-endian WKB
+    pub fn geometry_wkb(&self, code: &str) -> Option<Vec<u8>> {
+        self.geometry(code).map(|geometry| wkb::to_wkb(&geometry))
+    }
+
+    /// Find all regions intersecting a MultiPolygon geometry
+    ///
+    /// Regions touching the geometry only along their boundary are included.
+    ///
+    /// # Arguments
+    ///
+    /// * `geometry` - The query geometry, e.g. parsed with [`wkt::from_wkt`]
+    ///
+    /// # Returns
+    ///
+    /// The Properties of every intersecting region, once per region code
+    pub fn find_intersecting(&self, geometry: &Geometry) -> Vec<Properties> {
+        let query: Vec<Polygon> = geometry
+            .coordinates
+            .iter()
+            .filter_map(|polygon_coords| {
+                let mut rings = polygon_coords.iter().map(|ring| ring_to_points(ring));
+                let exterior = rings.next().filter(|ring| !ring.is_empty())?;
+                Some(Polygon::new(exterior, rings.collect(), None))
+            })
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for (polygon, props) in &self.polygons {
+            if seen.contains(props.code.as_str()) {
+                continue;
+            }
+            if query
+                .iter()
+                .any(|q| intersect::polygons_intersect(polygon, q))
+            {
+                seen.insert(props.code.as_str());
+                result.push(props.clone());
+            }
+        }
+        result
+    }
+
+    /// Serialise the regions matching `filter` as a GeoJSON string
+    ///
+    /// See [`ReverseJp::feature_collection`] for how regions are assembled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "embedded")] {
+    /// use reversejp::{AreaCode, ReverseJp};
+    ///
+    /// let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+    /// let codes: Vec<AreaCode> = reverse_jp
+    ///     .find_properties(139.7670, 35.6812)
+    ///     .into_iter()
+    ///     .map(|p| p.code)
+    ///     .collect();
+    /// let geojson = reverse_jp.to_geojson(|p| codes.contains(&p.code)).unwrap();
+    /// assert!(geojson.contains("1310100"));
+    /// # }
+    /// ```
+    pub fn to_geojson<F>(&self, filter: F) -> Result<String, Box<dyn Error>>
+    where
+        F: Fn(&Properties) -> bool,
+    {
+        Ok(serde_json::to_string(&self.feature_collection(filter))?)
+    }
 }
 
 #[cfg(test)]
@@ -340,6 +778,7 @@ mod tests {
         assert_eq!(properties.len(), 0);
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_with_embedded_data() {
         let reverse_jp = ReverseJp::with_embedded_data().unwrap();
@@ -354,8 +793,247 @@ mod tests {
             .iter()
             .any(|p| p.name == "東京都" || p.en_name == "Tokyo");
         assert!(found_tokyo);
+
+        let borrowed = reverse_jp.find_properties_ref(139.7670, 35.6812);
+        assert!(borrowed.into_iter().eq(properties.iter()));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_embedded_data_has_no_holes() {
+        // Inner rings used to be loaded as filled polygons of their own. The
+        // embedded data has none, so treating them as holes changes no lookup.
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        assert!(
+            reverse_jp
+                .polygons
+                .iter()
+                .all(|(polygon, _)| polygon.holes().is_empty())
+        );
+
+        // An enclave-like shape: the ring around 1.0 belongs to the region, the
+        // hole around 5.0 does not, unless another region fills it
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp
+            .load_geojson(
+                r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"code": "outer", "name": "外"},
+                 "geometry": {"type": "MultiPolygon", "coordinates": [[
+                    [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                    [[4, 4], [6, 4], [6, 6], [4, 6], [4, 4]]]]}},
+                {"type": "Feature", "properties": {"code": "inner", "name": "内"},
+                 "geometry": {"type": "MultiPolygon", "coordinates": [[
+                    [[4, 4], [6, 4], [6, 6], [4, 6], [4, 4]]]]}}]}"#,
+            )
+            .unwrap();
+        let codes = |x, y| -> Vec<String> {
+            reverse_jp
+                .find_properties(x, y)
+                .into_iter()
+                .map(|p| p.code.into())
+                .collect()
+        };
+        assert_eq!(codes(1.0, 1.0), ["outer"]);
+        assert_eq!(codes(5.0, 5.0), ["inner"]);
+    }
+
+    #[test]
+    fn test_holes_are_excluded_from_lookup() {
+        let mut reverse_jp = ReverseJp::new();
+        let json = r#"{
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [[
+                        [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+                        [[4.0, 4.0], [6.0, 4.0], [6.0, 6.0], [4.0, 6.0], [4.0, 4.0]]
+                    ]]
+                },
+                "properties": {"code": "000001", "name": "テスト"}
+            }]
+        }"#;
+        reverse_jp.load_geojson(json).unwrap();
+
+        assert_eq!(reverse_jp.polygons.len(), 1);
+        assert_eq!(reverse_jp.polygons[0].0.holes().len(), 1);
+        assert_eq!(reverse_jp.find_properties(1.0, 1.0).len(), 1);
+        assert!(reverse_jp.find_properties(5.0, 5.0).is_empty());
+
+        let geometry = reverse_jp.geometry("000001").unwrap();
+        assert_eq!(geometry.coordinates.len(), 1);
+        assert_eq!(geometry.coordinates[0].len(), 2);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_load_bytes() {
+        let mut from_zip = ReverseJp::new();
+        from_zip.load_bytes(EMBEDDED_CLASS10S_DATA).unwrap();
+        let mut from_json = ReverseJp::new();
+        from_json
+            .load_bytes(get_class10s_data().unwrap().as_bytes())
+            .unwrap();
+
+        assert!(!from_zip.polygons.is_empty());
+        assert_eq!(from_zip.polygons.len(), from_json.polygons.len());
+        let props = from_zip.find_properties(139.7670, 35.6812);
+        assert!(props.iter().any(|p| p.code == "130010"));
+
+        assert!(ReverseJp::new().load_bytes(b"not json").is_err());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_regions() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let regions = reverse_jp.regions();
+        let codes: HashSet<&str> = regions.iter().map(|p| p.code.as_str()).collect();
+        assert_eq!(codes.len(), regions.len());
+        assert!(codes.contains("011000") && codes.contains("1310100"));
+        assert_eq!(
+            regions.len(),
+            reverse_jp.feature_collection(|_| true).features.len()
+        );
+    }
+
+    #[test]
+    fn test_regions_sharing_a_code() {
+        let mut reverse_jp = ReverseJp::new();
+        let json = r#"{
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]],
+                        [[[2.0, 0.0], [3.0, 0.0], [3.0, 1.0], [2.0, 1.0], [2.0, 0.0]]]
+                    ]
+                },
+                "properties": {"code": "000001", "name": "テスト"}
+            }, {
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [[[[4.0, 0.0], [5.0, 0.0], [5.0, 1.0], [4.0, 1.0], [4.0, 0.0]]]]
+                },
+                "properties": {"code": "000001", "name": "別名"}
+            }]
+        }"#;
+        reverse_jp.load_geojson(json).unwrap();
+
+        let names: Vec<&str> = reverse_jp
+            .regions()
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["テスト", "別名"]);
+        assert_eq!(reverse_jp.find_region_indices(0.5, 0.5), vec![0]);
+        assert_eq!(reverse_jp.find_region_indices(2.5, 0.5), vec![0]);
+        assert_eq!(reverse_jp.find_region_indices(4.5, 0.5), vec![1]);
+        assert!(reverse_jp.find_region_indices(10.0, 10.0).is_empty());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_geometry_merges_features() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        // 宗谷地方 is split across several features in class10s.json
+        let parts = reverse_jp
+            .polygons
+            .iter()
+            .filter(|(_, props)| props.code == "011000")
+            .count();
+        let geometry = reverse_jp.geometry("011000").unwrap();
+        assert_eq!(geometry.geometry_type, "MultiPolygon");
+        assert_eq!(geometry.coordinates.len(), parts);
+
+        assert!(reverse_jp.geometry("not-a-code").is_none());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_to_geojson() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let geojson = reverse_jp
+            .to_geojson(|p| p.code == "011000" || p.code == "1310100")
+            .unwrap();
+
+        let collection: FeatureCollection = serde_json::from_str(&geojson).unwrap();
+        assert_eq!(collection.feature_type, "FeatureCollection");
+        assert_eq!(collection.features.len(), 2);
+        assert_eq!(collection.features[0].properties.code, "011000");
+        assert_eq!(
+            collection.features[0].geometry,
+            reverse_jp.geometry("011000").unwrap()
+        );
+        assert_eq!(collection.features[1].properties.name, "千代田区");
+        assert_eq!(collection.features[1].properties.en_name, "Chiyoda City");
+    }
+
+    #[test]
+    fn test_wkt_wkb_custom_layer() {
+        let mut reverse_jp = ReverseJp::new();
+        let props = |code: &str| Properties {
+            code: code.into(),
+            name: code.to_string(),
+            en_name: String::new(),
+            kana: None,
+        };
+        reverse_jp
+            .load_wkt("POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))", props("wkt"))
+            .unwrap();
+        let wkb = wkb::to_wkb(&wkt::from_wkt("POLYGON ((20 0, 30 0, 30 10, 20 0))").unwrap());
+        reverse_jp.load_wkb(&wkb, props("wkb")).unwrap();
+        assert!(reverse_jp.load_wkt("POINT (1 1)", props("bad")).is_err());
+        let point = Geometry {
+            geometry_type: "Point".to_string(),
+            coordinates: Vec::new(),
+        };
+        assert!(reverse_jp.add_region(point, props("bad")).is_err());
+
+        assert_eq!(reverse_jp.find_properties(5.0, 5.0)[0].code, "wkt");
+        assert_eq!(reverse_jp.find_properties(29.0, 1.0)[0].code, "wkb");
+        assert_eq!(reverse_jp.geometry_wkb("wkb").unwrap(), wkb);
+        assert_eq!(
+            reverse_jp.geometry_wkt("wkt").unwrap(),
+            "MULTIPOLYGON(((0 0,10 0,10 10,0 10,0 0)))"
+        );
+
+        let query = wkt::from_wkt("POLYGON ((8 1, 25 1, 25 2, 8 2, 8 1))").unwrap();
+        let codes: Vec<String> = reverse_jp
+            .find_intersecting(&query)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert_eq!(codes, vec!["wkt", "wkb"]);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_find_intersecting_embedded() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        // A small box around Tokyo Station
+        let query = wkt::from_wkt(
+            "POLYGON ((139.76 35.67, 139.775 35.67, 139.775 35.69, 139.76 35.69, 139.76 35.67))",
+        )
+        .unwrap();
+        let codes: Vec<String> = reverse_jp
+            .find_intersecting(&query)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert!(codes.contains(&"130010".to_string()));
+        assert!(codes.contains(&"1310100".to_string()));
+        assert!(codes.contains(&"1310200".to_string()));
+        assert!(!codes.contains(&"270000".to_string()));
     }
 
+    #[cfg(feature = "embedded")]
     #[test]
     fn test_all_jp_cities_included() {
         // Get all Japanese cities from the cities-json crate
@@ -0,0 +1,221 @@
+//! Localised region names.
+//!
+//! Layers do not always carry English names or readings. Names missing from
+//! the loaded data can be resolved from `area.json`, falling back to a
+//! Hepburn romanisation of the reading.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::area::AreaData;
+//! use reversejp::locale::Locale;
+//! use reversejp::ReverseJp;
+//!
+//! let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//! reverse_jp.resolve_names(&AreaData::with_embedded_data().unwrap());
+//!
+//! let shibuya = reverse_jp
+//!     .find_properties(139.7016, 35.6619)
+//!     .into_iter()
+//!     .find(|p| p.code == "1311300")
+//!     .unwrap();
+//! assert_eq!(shibuya.localized_name(Locale::Ja), "渋谷区");
+//! assert_eq!(shibuya.localized_name("ja-Hira".parse().unwrap()), "しぶやく");
+//! assert_eq!(shibuya.localized_name("ja-Kana".parse().unwrap()), "シブヤク");
+//! assert_eq!(shibuya.localized_name(Locale::En), "Shibuya City");
+//! # }
+//! ```
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::area::AreaData;
+use crate::kana;
+use crate::{Properties, ReverseJp};
+
+/// Language in which region names are returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    /// Japanese name, e.g. `渋谷区`
+    #[default]
+    Ja,
+    /// Reading of the Japanese name in hiragana, e.g. `しぶやく`
+    JaHira,
+    /// Reading of the Japanese name in katakana, e.g. `シブヤク`
+    JaKana,
+    /// English name, e.g. `Shibuya City`
+    En,
+}
+
+impl FromStr for Locale {
+    type Err = Box<dyn Error>;
+
+    /// Parse a language tag such as `ja`, `ja-Hira`, `ja-Kana` or `en-US`
+    ///
+    /// `ja-Hrkt` (hiragana and katakana) gives the reading as stored, in hiragana.
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        let mut subtags = tag.split(['-', '_']).map(str::to_ascii_lowercase);
+        match subtags.next().as_deref() {
+            Some("ja") => Ok(subtags
+                .find_map(|s| match s.as_str() {
+                    "hira" | "hrkt" => Some(Locale::JaHira),
+                    "kana" => Some(Locale::JaKana),
+                    _ => None,
+                })
+                .unwrap_or(Locale::Ja)),
+            Some("en") => Ok(Locale::En),
+            _ => Err(format!("Unsupported locale: {}", tag).into()),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Locale::Ja => "ja",
+            Locale::JaHira => "ja-Hira",
+            Locale::JaKana => "ja-Kana",
+            Locale::En => "en",
+        })
+    }
+}
+
+/// Romanise a reading as a name, e.g. `しぶやく` becomes `Shibuyaku`
+pub fn romanize(kana: &str) -> String {
+    kana::to_hepburn(kana)
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+impl Properties {
+    /// The name of the region in the given locale
+    ///
+    /// Falls back to the romanised reading when the English name is missing,
+    /// and to the Japanese name when no reading is known.
+    pub fn localized_name(&self, locale: Locale) -> Cow<'_, str> {
+        match (locale, &self.kana) {
+            (Locale::En, _) if !self.en_name.is_empty() => Cow::Borrowed(&self.en_name),
+            (Locale::En, Some(kana)) => Cow::Owned(romanize(kana)),
+            (Locale::JaHira, Some(kana)) => Cow::Owned(kana::to_hiragana(kana)),
+            (Locale::JaKana, Some(kana)) => Cow::Owned(kana::to_katakana(kana)),
+            _ => Cow::Borrowed(&self.name),
+        }
+    }
+
+    /// Fill in a missing English name and reading
+    ///
+    /// The English name and reading are taken from `area.json` when the code
+    /// is found there; otherwise the English name is romanised from the
+    /// reading, if any.
+    pub fn resolve_names(&mut self, area: &AreaData) {
+        let entry = area.find(&self.code).map(|(_, entry)| entry);
+        if self.kana.is_none() {
+            self.kana = entry.and_then(|entry| entry.kana.clone());
+        }
+        if self.en_name.is_empty() {
+            if let Some(entry) = entry.filter(|entry| !entry.en_name.is_empty()) {
+                self.en_name = entry.en_name.clone();
+            } else if let Some(kana) = &self.kana {
+                self.en_name = romanize(kana);
+            }
+        }
+    }
+}
+
+impl ReverseJp {
+    /// Fill in missing English names and readings of every loaded region
+    ///
+    /// See [`Properties::resolve_names`].
+    pub fn resolve_names(&mut self, area: &AreaData) {
+        for (_, props) in &mut self.polygons {
+            props.resolve_names(area);
+        }
+        self.adjacency = OnceLock::new();
+        self.region_index = OnceLock::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!("ja".parse::<Locale>().unwrap(), Locale::Ja);
+        assert_eq!("ja-JP".parse::<Locale>().unwrap(), Locale::Ja);
+        assert_eq!("ja-Kana".parse::<Locale>().unwrap(), Locale::JaKana);
+        assert_eq!("ja-Hira".parse::<Locale>().unwrap(), Locale::JaHira);
+        assert_eq!("ja_hrkt".parse::<Locale>().unwrap(), Locale::JaHira);
+        assert_eq!("ja-JP-kana".parse::<Locale>().unwrap(), Locale::JaKana);
+        assert_eq!("en-US".parse::<Locale>().unwrap(), Locale::En);
+        assert!("fr".parse::<Locale>().is_err());
+        assert_eq!(Locale::JaKana.to_string(), "ja-Kana");
+        assert_eq!(Locale::JaHira.to_string(), "ja-Hira");
+    }
+
+    #[test]
+    fn test_romanize() {
+        assert_eq!(romanize("しぶやく"), "Shibuyaku");
+        assert_eq!(romanize("おおさか・とうきょう"), "Osaka Tokyo");
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_resolve_embedded_names() {
+        let area = AreaData::with_embedded_data().unwrap();
+        let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        reverse_jp.resolve_names(&area);
+
+        // The class10s layer has no English name for 宗谷地方
+        let soya = reverse_jp.feature_collection(|p| p.code == "011000");
+        assert_eq!(soya.features[0].properties.en_name, "Soya Region");
+
+        let props = reverse_jp.find_properties(139.7016, 35.6619);
+        let shibuya = props.iter().find(|p| p.code == "1311300").unwrap();
+        assert_eq!(shibuya.kana.as_deref(), Some("しぶやく"));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_resolve_from_kana() {
+        let area = AreaData::with_embedded_data().unwrap();
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp
+            .load_wkt(
+                "POLYGON ((0 0, 10 0, 10 10, 0 10, 0 0))",
+                Properties {
+                    code: "custom".into(),
+                    name: "新宿御苑".to_string(),
+                    en_name: String::new(),
+                    kana: Some("しんじゅくぎょえん".to_string()),
+                },
+            )
+            .unwrap();
+        reverse_jp.resolve_names(&area);
+
+        let props = &reverse_jp.find_properties(5.0, 5.0)[0];
+        assert_eq!(props.en_name, "Shinjukugyoen");
+        assert_eq!(props.localized_name(Locale::JaHira), "しんじゅくぎょえん");
+        assert_eq!(props.localized_name(Locale::JaKana), "シンジュクギョエン");
+
+        let unnamed = Properties {
+            code: "custom".into(),
+            name: "無名".to_string(),
+            en_name: String::new(),
+            kana: None,
+        };
+        assert_eq!(unnamed.localized_name(Locale::En), "無名");
+        assert_eq!(unnamed.localized_name(Locale::JaKana), "無名");
+        assert_eq!(unnamed.localized_name(Locale::JaHira), "無名");
+    }
+}
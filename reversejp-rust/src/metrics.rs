@@ -0,0 +1,401 @@
+//! Geodesic measurements of regions.
+//!
+//! Areas and lengths are computed on a sphere with the mean Earth radius,
+//! which is accurate to about 0.5% anywhere in Japan.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Geometry, ReverseJp};
+
+/// Mean Earth radius in kilometres
+pub const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+/// Measurements of a region
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RegionMetrics {
+    /// Area in square kilometres, holes excluded
+    #[serde(rename = "areaKm2")]
+    pub area_km2: f64,
+    /// Total length of all rings, holes included, in kilometres
+    #[serde(rename = "perimeterKm")]
+    pub perimeter_km: f64,
+    /// Area-weighted centroid as `[longitude, latitude]`, may lie outside the region
+    pub centroid: [f64; 2],
+    /// Pole of inaccessibility of the largest polygon as `[longitude, latitude]`,
+    /// always inside the region and suited for label placement
+    #[serde(rename = "labelPoint")]
+    pub label_point: [f64; 2],
+    /// Bounding box as `[west, south, east, north]`
+    pub bbox: [f64; 4],
+}
+
+// Geodesic area of a ring in km², sign depending on its orientation
+fn ring_area_km2(ring: &[[f64; 2]]) -> f64 {
+    let n = ring.len();
+    if n < 3 {
+        return 0.0;
+    }
+    let mut total = 0.0;
+    for i in 0..n {
+        let lower = ring[i];
+        let middle = ring[(i + 1) % n];
+        let upper = ring[(i + 2) % n];
+        total += (upper[0].to_radians() - lower[0].to_radians()) * middle[1].to_radians().sin();
+    }
+    total * EARTH_RADIUS_KM * EARTH_RADIUS_KM / 2.0
+}
+
+fn haversine_km(a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (lat1, lat2) = (a[1].to_radians(), b[1].to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b[0] - a[0]).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+// Planar signed area and first moments of a ring
+fn ring_moments(ring: &[[f64; 2]]) -> (f64, f64, f64) {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for i in 0..ring.len() {
+        let a = ring[i];
+        let b = ring[(i + 1) % ring.len()];
+        let cross = a[0] * b[1] - b[0] * a[1];
+        area += cross;
+        cx += (a[0] + b[0]) * cross;
+        cy += (a[1] + b[1]) * cross;
+    }
+    (area / 2.0, cx / 6.0, cy / 6.0)
+}
+
+fn polygon_area_km2(polygon: &[Vec<[f64; 2]>]) -> f64 {
+    polygon
+        .iter()
+        .enumerate()
+        .map(|(i, ring)| {
+            let area = ring_area_km2(ring).abs();
+            if i == 0 { area } else { -area }
+        })
+        .sum()
+}
+
+/// Compute the measurements of a MultiPolygon geometry
+///
+/// Returns `None` for an empty geometry.
+pub fn measure(geometry: &Geometry) -> Option<RegionMetrics> {
+    let mut bbox = [
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    ];
+    let (mut area_km2, mut perimeter_km) = (0.0, 0.0);
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    let mut largest: Option<(&Vec<Vec<[f64; 2]>>, f64)> = None;
+
+    for polygon in &geometry.coordinates {
+        let polygon_area = polygon_area_km2(polygon);
+        area_km2 += polygon_area;
+        if largest.is_none_or(|(_, a)| polygon_area > a) {
+            largest = Some((polygon, polygon_area));
+        }
+
+        for (i, ring) in polygon.iter().enumerate() {
+            for coord in ring {
+                bbox[0] = bbox[0].min(coord[0]);
+                bbox[1] = bbox[1].min(coord[1]);
+                bbox[2] = bbox[2].max(coord[0]);
+                bbox[3] = bbox[3].max(coord[1]);
+            }
+            perimeter_km += ring
+                .windows(2)
+                .map(|w| haversine_km(w[0], w[1]))
+                .sum::<f64>();
+
+            // Exteriors add to the centroid and holes remove from it,
+            // whatever the orientation of the ring
+            let (a, x, y) = ring_moments(ring);
+            let sign = if (i == 0) == (a >= 0.0) { 1.0 } else { -1.0 };
+            area += sign * a;
+            cx += sign * x;
+            cy += sign * y;
+        }
+    }
+
+    let (polygon, _) = largest?;
+    let centroid = if area != 0.0 {
+        [cx / area, cy / area]
+    } else {
+        [(bbox[0] + bbox[2]) / 2.0, (bbox[1] + bbox[3]) / 2.0]
+    };
+
+    Some(RegionMetrics {
+        area_km2,
+        perimeter_km,
+        centroid,
+        label_point: pole_of_inaccessibility(polygon, 1e-5),
+        bbox,
+    })
+}
+
+// Signed distance from a point to the polygon outline, positive inside
+fn signed_distance(p: [f64; 2], polygon: &[Vec<[f64; 2]>]) -> f64 {
+    let mut inside = false;
+    let mut min_sq = f64::INFINITY;
+    for ring in polygon {
+        let n = ring.len();
+        for i in 0..n {
+            let a = ring[i];
+            let b = ring[(i + n - 1) % n];
+            if (a[1] > p[1]) != (b[1] > p[1])
+                && p[0] < (b[0] - a[0]) * (p[1] - a[1]) / (b[1] - a[1]) + a[0]
+            {
+                inside = !inside;
+            }
+
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            let len2 = dx * dx + dy * dy;
+            let t = if len2 == 0.0 {
+                0.0
+            } else {
+                (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len2).clamp(0.0, 1.0)
+            };
+            let (x, y) = (a[0] + t * dx - p[0], a[1] + t * dy - p[1]);
+            min_sq = min_sq.min(x * x + y * y);
+        }
+    }
+    let distance = min_sq.sqrt();
+    if inside { distance } else { -distance }
+}
+
+struct Cell {
+    center: [f64; 2],
+    half: f64,
+    distance: f64,
+    max: f64,
+}
+
+impl Cell {
+    fn new(center: [f64; 2], half: f64, polygon: &[Vec<[f64; 2]>]) -> Self {
+        let distance = signed_distance(center, polygon);
+        Cell {
+            center,
+            half,
+            distance,
+            max: distance + half * std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.max == other.max
+    }
+}
+
+impl Eq for Cell {}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.max.total_cmp(&other.max)
+    }
+}
+
+// Polylabel: the interior point farthest from the outline, found by
+// subdividing the bounding box. Longitudes are scaled by the cosine of the
+// latitude so distances are measured evenly in both directions.
+fn pole_of_inaccessibility(polygon: &[Vec<[f64; 2]>], precision: f64) -> [f64; 2] {
+    let Some(exterior) = polygon.first().filter(|ring| !ring.is_empty()) else {
+        return [0.0, 0.0];
+    };
+    let mean_lat = exterior.iter().map(|c| c[1]).sum::<f64>() / exterior.len() as f64;
+    let scale = mean_lat.to_radians().cos().max(1e-6);
+    let projected: Vec<Vec<[f64; 2]>> = polygon
+        .iter()
+        .map(|ring| ring.iter().map(|c| [c[0] * scale, c[1]]).collect())
+        .collect();
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (
+        f64::INFINITY,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NEG_INFINITY,
+    );
+    for c in &projected[0] {
+        min_x = min_x.min(c[0]);
+        min_y = min_y.min(c[1]);
+        max_x = max_x.max(c[0]);
+        max_y = max_y.max(c[1]);
+    }
+    let (width, height) = (max_x - min_x, max_y - min_y);
+    let cell_size = width.min(height);
+    if cell_size == 0.0 {
+        return exterior[0];
+    }
+
+    let mut queue = BinaryHeap::new();
+    let half = cell_size / 2.0;
+    let mut x = min_x;
+    while x < max_x {
+        let mut y = min_y;
+        while y < max_y {
+            queue.push(Cell::new([x + half, y + half], half, &projected));
+            y += cell_size;
+        }
+        x += cell_size;
+    }
+
+    // Start from the centroid of the exterior, then the bbox center
+    let (a, cx, cy) = ring_moments(&projected[0]);
+    let mut best = if a != 0.0 {
+        Cell::new([cx / a, cy / a], 0.0, &projected)
+    } else {
+        Cell::new(projected[0][0], 0.0, &projected)
+    };
+    let bbox_cell = Cell::new([min_x + width / 2.0, min_y + height / 2.0], 0.0, &projected);
+    if bbox_cell.distance > best.distance {
+        best = bbox_cell;
+    }
+
+    while let Some(cell) = queue.pop() {
+        if cell.distance > best.distance {
+            best = Cell::new(cell.center, 0.0, &projected);
+        }
+        if cell.max - best.distance <= precision {
+            continue;
+        }
+        let half = cell.half / 2.0;
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+            queue.push(Cell::new(
+                [cell.center[0] + dx * half, cell.center[1] + dy * half],
+                half,
+                &projected,
+            ));
+        }
+    }
+
+    [best.center[0] / scale, best.center[1]]
+}
+
+impl ReverseJp {
+    /// Compute the area, perimeter, centroid, label point and bounding box of
+    /// a region
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The region code, e.g. `130010` or `1310100`
+    ///
+    /// # Returns
+    ///
+    /// The measurements of the region, or `None` if the code is not loaded
+    pub fn metrics(&self, code: &str) -> Option<RegionMetrics> {
+        measure(&self.geometry(code)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(coordinates: Vec<Vec<Vec<[f64; 2]>>>) -> Geometry {
+        Geometry {
+            geometry_type: "MultiPolygon".to_string(),
+            coordinates,
+        }
+    }
+
+    #[test]
+    fn test_measure_square() {
+        let square = vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]];
+        let metrics = measure(&geometry(vec![vec![square.clone()]])).unwrap();
+
+        // One degree is about 111.2 km at the equator
+        assert!(
+            (metrics.area_km2 - 12364.0).abs() < 10.0,
+            "{}",
+            metrics.area_km2
+        );
+        assert!(
+            (metrics.perimeter_km - 444.8).abs() < 0.5,
+            "{}",
+            metrics.perimeter_km
+        );
+        assert_eq!(metrics.centroid, [0.5, 0.5]);
+        assert_eq!(metrics.bbox, [0.0, 0.0, 1.0, 1.0]);
+        assert!((metrics.label_point[0] - 0.5).abs() < 1e-3);
+        assert!((metrics.label_point[1] - 0.5).abs() < 1e-3);
+
+        // Orientation does not matter
+        let reversed: Vec<[f64; 2]> = square.iter().rev().copied().collect();
+        let metrics_reversed = measure(&geometry(vec![vec![reversed]])).unwrap();
+        assert!((metrics_reversed.area_km2 - metrics.area_km2).abs() < 1e-9);
+        assert_eq!(metrics_reversed.centroid, metrics.centroid);
+    }
+
+    #[test]
+    fn test_label_point_inside_concave_polygon() {
+        // A "C" shape whose centroid lies in the gap
+        let c_shape = vec![
+            [0.0, 0.0],
+            [3.0, 0.0],
+            [3.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [3.0, 2.0],
+            [3.0, 3.0],
+            [0.0, 3.0],
+            [0.0, 0.0],
+        ];
+        let metrics = measure(&geometry(vec![vec![c_shape.clone()]])).unwrap();
+        let polygon = vec![c_shape];
+        assert!(signed_distance(metrics.centroid, &polygon) < 0.0);
+        assert!(signed_distance(metrics.label_point, &polygon) > 0.0);
+    }
+
+    #[test]
+    fn test_hole_reduces_area() {
+        let outer = vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0], [0.0, 0.0]];
+        let hole = vec![[0.5, 0.5], [1.5, 0.5], [1.5, 1.5], [0.5, 1.5], [0.5, 0.5]];
+        let full = measure(&geometry(vec![vec![outer.clone()]])).unwrap();
+        let polygon = vec![outer, hole];
+        let holed = measure(&geometry(vec![polygon.clone()])).unwrap();
+        assert!((holed.area_km2 / full.area_km2 - 0.75).abs() < 1e-3);
+        assert!(holed.perimeter_km > full.perimeter_km);
+        assert!(signed_distance(holed.label_point, &polygon) > 0.0);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_metrics_embedded() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        // 千代田区 covers about 11.7 km²
+        let metrics = reverse_jp.metrics("1310100").unwrap();
+        assert!(
+            metrics.area_km2 > 10.0 && metrics.area_km2 < 13.5,
+            "{}",
+            metrics.area_km2
+        );
+        assert!(metrics.perimeter_km > 10.0 && metrics.perimeter_km < 40.0);
+
+        let [lng, lat] = metrics.label_point;
+        assert!(lng > metrics.bbox[0] && lng < metrics.bbox[2]);
+        assert!(lat > metrics.bbox[1] && lat < metrics.bbox[3]);
+        let codes: Vec<String> = reverse_jp
+            .find_properties(lng, lat)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert!(codes.contains(&"1310100".to_string()));
+
+        assert!(reverse_jp.metrics("not-a-code").is_none());
+    }
+}
@@ -0,0 +1,569 @@
+//! Mapbox Vector Tile (MVT) rendering of the loaded region boundaries.
+//!
+//! Tiles follow the [Mapbox Vector Tile specification v2.1] and use the
+//! XYZ tiling scheme in Web Mercator. Each tile contains two polygon layers:
+//!
+//! * `class10s`: forecast regions (6-digit codes, e.g. `130010`)
+//! * `class20s`: municipalities (7-digit codes, e.g. `1310100`)
+//!
+//! Every feature carries `code`, `name` and `enName` attributes. Regions of
+//! other levels, e.g. loaded prefectures or dissolved layers, are left out.
+//!
+//! Zoom levels go up to [`MAX_ZOOM`].
+//!
+//! [Mapbox Vector Tile specification v2.1]: https://github.com/mapbox/vector-tile-spec/tree/master/2.1
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts::PI;
+
+use crate::{AreaLevel, Properties, ReverseJp, SOURCE_LAYER};
+
+/// The highest supported zoom level
+pub const MAX_ZOOM: u8 = 24;
+
+/// Tile extent, the number of integer units along each tile edge
+pub const EXTENT: u32 = 4096;
+
+/// Extra units drawn around each tile so that strokes do not clip at tile edges
+pub const BUFFER: u32 = 64;
+
+/// The name of the layer holding forecast regions
+pub const CLASS10S_LAYER: &str = "class10s";
+
+/// The name of the layer holding municipalities
+pub const CLASS20S_LAYER: &str = "class20s";
+
+const CMD_MOVE_TO: u32 = 1;
+const CMD_LINE_TO: u32 = 2;
+const CMD_CLOSE_PATH: u32 = 7;
+const GEOM_TYPE_POLYGON: u64 = 3;
+
+// GeoJSON polygon coordinates: exterior ring followed by holes
+type PolygonCoords = Vec<Vec<[f64; 2]>>;
+
+// Check that a tile exists: `z` at most MAX_ZOOM, `x` and `y` below 2^z
+fn check_tile(z: u8, x: u32, y: u32) -> Result<(), Box<dyn Error>> {
+    if z > MAX_ZOOM {
+        return Err(format!("Zoom level {} is above the maximum of {}", z, MAX_ZOOM).into());
+    }
+    let n = 1u32 << z;
+    if x >= n || y >= n {
+        return Err(format!("Tile {}/{}/{} is outside the grid of zoom {}", z, x, y, z).into());
+    }
+    Ok(())
+}
+
+/// Longitude/latitude bounding box of a tile, as `[west, south, east, north]`
+///
+/// Fails if `z` is above [`MAX_ZOOM`] or `x` or `y` is outside the grid.
+pub fn tile_bounds(z: u8, x: u32, y: u32) -> Result<[f64; 4], Box<dyn Error>> {
+    check_tile(z, x, y)?;
+    let n = f64::from(1u32 << z);
+    let lon = |x: f64| x / n * 360.0 - 180.0;
+    let lat = |y: f64| (PI * (1.0 - 2.0 * y / n)).sinh().atan().to_degrees();
+    Ok([
+        lon(f64::from(x)),
+        lat(f64::from(y + 1)),
+        lon(f64::from(x + 1)),
+        lat(f64::from(y)),
+    ])
+}
+
+/// All `(x, y)` tiles at zoom `z` covering a `[west, south, east, north]` box
+///
+/// Useful to pre-generate a tile archive for the area covered by the data.
+/// Fails if `z` is above [`MAX_ZOOM`].
+pub fn tiles_covering(
+    bbox: [f64; 4],
+    z: u8,
+) -> Result<impl Iterator<Item = (u32, u32)>, Box<dyn Error>> {
+    check_tile(z, 0, 0)?;
+    let max = (1u32 << z) - 1;
+    let [x0, y1] = world_position(z, bbox[0], bbox[1]);
+    let [x1, y0] = world_position(z, bbox[2], bbox[3]);
+    let clamp = |v: f64| (v.floor().max(0.0) as u32).min(max);
+    let (x0, x1, y0, y1) = (clamp(x0), clamp(x1), clamp(y0), clamp(y1));
+    Ok((x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x, y))))
+}
+
+// Position in tile units at zoom `z`, y growing southwards
+fn world_position(z: u8, lon: f64, lat: f64) -> [f64; 2] {
+    let n = f64::from(1u32 << z);
+    let lat = lat.clamp(-85.051_128_78, 85.051_128_78).to_radians();
+    [
+        (lon + 180.0) / 360.0 * n,
+        (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n,
+    ]
+}
+
+// The tile layer of a region by the level of its code, if it has one
+fn layer_for(props: &Properties) -> Option<&'static str> {
+    match props.code.level()? {
+        AreaLevel::Class10 => Some(CLASS10S_LAYER),
+        AreaLevel::Class20 => Some(CLASS20S_LAYER),
+        _ => None,
+    }
+}
+
+impl ReverseJp {
+    /// Render the loaded region boundaries as a Mapbox Vector Tile
+    ///
+    /// Regions split across several source features are merged into a single
+    /// tile feature. Tiles without any region still contain both (empty) layers.
+    ///
+    /// # Arguments
+    ///
+    /// * `z` - Zoom level, at most [`MAX_ZOOM`]
+    /// * `x` - Tile column, below 2^z
+    /// * `y` - Tile row, counted from the north, below 2^z
+    ///
+    /// # Returns
+    ///
+    /// The protobuf encoded tile, uncompressed, or an error for a tile outside the grid
+    pub fn vector_tile(&self, z: u8, x: u32, y: u32) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut class10s = LayerBuilder::new(CLASS10S_LAYER);
+        let mut class20s = LayerBuilder::new(CLASS20S_LAYER);
+
+        let [west, south, east, north] = tile_bounds(z, x, y)?;
+        let margin_x = (east - west) * f64::from(BUFFER) / f64::from(EXTENT);
+        let margin_y = (north - south) * f64::from(BUFFER) / f64::from(EXTENT);
+
+        // Group polygons by region code, in load order
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut regions: Vec<(&Properties, Vec<PolygonCoords>)> = Vec::new();
+        for ((polygon, props), &layer) in self.polygons.iter().zip(&self.layers) {
+            if layer != SOURCE_LAYER || layer_for(props).is_none() {
+                continue;
+            }
+            let rect = polygon.rect();
+            if rect.max.x < west - margin_x
+                || rect.min.x > east + margin_x
+                || rect.max.y < south - margin_y
+                || rect.min.y > north + margin_y
+            {
+                continue;
+            }
+            let idx = *index.entry(props.code.as_str()).or_insert_with(|| {
+                regions.push((props, Vec::new()));
+                regions.len() - 1
+            });
+            regions[idx].1.push(crate::polygon_coordinates(polygon));
+        }
+
+        for (props, polygons) in regions {
+            let mut cursor = [0, 0];
+            let mut geometry = Vec::new();
+            for polygon in &polygons {
+                encode_polygon(z, x, y, polygon, &mut cursor, &mut geometry);
+            }
+            if geometry.is_empty() {
+                continue;
+            }
+
+            let layer = match layer_for(props) {
+                Some(CLASS20S_LAYER) => &mut class20s,
+                _ => &mut class10s,
+            };
+            layer.add_feature(props, geometry);
+        }
+
+        let mut tile = Vec::new();
+        for layer in [class10s, class20s] {
+            write_bytes(&mut tile, 3, &layer.encode());
+        }
+        Ok(tile)
+    }
+}
+
+// Append the MVT commands of one polygon, skipping rings that vanish in the tile.
+// `cursor` carries the pen position across the polygons of a feature.
+fn encode_polygon(
+    z: u8,
+    x: u32,
+    y: u32,
+    polygon: &[Vec<[f64; 2]>],
+    cursor: &mut [i64; 2],
+    out: &mut Vec<u32>,
+) {
+    let origin = [f64::from(x), f64::from(y)];
+    let extent = f64::from(EXTENT);
+    let (min, max) = (-f64::from(BUFFER), extent + f64::from(BUFFER));
+
+    let mut ring_commands = Vec::new();
+    for (i, ring) in polygon.iter().enumerate() {
+        let projected: Vec<[f64; 2]> = ring
+            .iter()
+            .map(|c| {
+                let [wx, wy] = world_position(z, c[0], c[1]);
+                [(wx - origin[0]) * extent, (wy - origin[1]) * extent]
+            })
+            .collect();
+
+        let mut points: Vec<[i64; 2]> = Vec::new();
+        for p in clip_ring(&projected, min, max) {
+            let p = [p[0].round() as i64, p[1].round() as i64];
+            if points.last() != Some(&p) {
+                points.push(p);
+            }
+        }
+        while points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        if points.len() < 3 {
+            // A hole cannot exist without its exterior
+            if i == 0 {
+                return;
+            }
+            continue;
+        }
+
+        // Exterior rings must have a positive area in tile coordinates, holes negative
+        let area = signed_area(&points);
+        if area == 0 {
+            if i == 0 {
+                return;
+            }
+            continue;
+        }
+        if (i == 0) != (area > 0) {
+            points.reverse();
+        }
+
+        ring_commands.push(command(CMD_MOVE_TO, 1));
+        for (j, p) in points.iter().enumerate() {
+            if j == 1 {
+                ring_commands.push(command(CMD_LINE_TO, points.len() as u32 - 1));
+            }
+            ring_commands.push(zigzag(p[0] - cursor[0]));
+            ring_commands.push(zigzag(p[1] - cursor[1]));
+            *cursor = *p;
+        }
+        ring_commands.push(command(CMD_CLOSE_PATH, 1));
+    }
+    out.extend(ring_commands);
+}
+
+fn signed_area(points: &[[i64; 2]]) -> i64 {
+    let mut sum = 0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        sum += a[0] * b[1] - b[0] * a[1];
+    }
+    sum
+}
+
+// Sutherland-Hodgman clipping of a ring against the square [min, max]²
+fn clip_ring(ring: &[[f64; 2]], min: f64, max: f64) -> Vec<[f64; 2]> {
+    let mut points = ring.to_vec();
+    for (axis, bound, keep_below) in [
+        (0, min, false),
+        (0, max, true),
+        (1, min, false),
+        (1, max, true),
+    ] {
+        if points.is_empty() {
+            break;
+        }
+        let inside = |p: &[f64; 2]| {
+            if keep_below {
+                p[axis] <= bound
+            } else {
+                p[axis] >= bound
+            }
+        };
+        let mut clipped = Vec::with_capacity(points.len());
+        for (i, current) in points.iter().enumerate() {
+            let previous = &points[(i + points.len() - 1) % points.len()];
+            if inside(current) != inside(previous) {
+                let t = (bound - previous[axis]) / (current[axis] - previous[axis]);
+                let mut p = [
+                    previous[0] + (current[0] - previous[0]) * t,
+                    previous[1] + (current[1] - previous[1]) * t,
+                ];
+                p[axis] = bound;
+                clipped.push(p);
+            }
+            if inside(current) {
+                clipped.push(*current);
+            }
+        }
+        points = clipped;
+    }
+    points
+}
+
+fn command(id: u32, count: u32) -> u32 {
+    (id & 0x7) | (count << 3)
+}
+
+fn zigzag(n: i64) -> u32 {
+    ((n << 1) ^ (n >> 63)) as u32
+}
+
+struct LayerBuilder {
+    name: &'static str,
+    keys: Vec<&'static str>,
+    values: Vec<String>,
+    value_index: HashMap<String, u32>,
+    features: Vec<Vec<u8>>,
+}
+
+impl LayerBuilder {
+    fn new(name: &'static str) -> Self {
+        LayerBuilder {
+            name,
+            keys: vec!["code", "name", "enName"],
+            values: Vec::new(),
+            value_index: HashMap::new(),
+            features: Vec::new(),
+        }
+    }
+
+    fn value(&mut self, value: &str) -> u32 {
+        if let Some(&idx) = self.value_index.get(value) {
+            return idx;
+        }
+        let idx = self.values.len() as u32;
+        self.values.push(value.to_string());
+        self.value_index.insert(value.to_string(), idx);
+        idx
+    }
+
+    fn add_feature(&mut self, props: &Properties, geometry: Vec<u32>) {
+        let mut tags = Vec::new();
+        for (key, value) in [props.code.as_str(), &props.name, &props.en_name]
+            .into_iter()
+            .enumerate()
+        {
+            if !value.is_empty() {
+                tags.push(key as u32);
+                tags.push(self.value(value));
+            }
+        }
+
+        let mut feature = Vec::new();
+        write_packed(&mut feature, 2, &tags);
+        write_varint_field(&mut feature, 3, GEOM_TYPE_POLYGON);
+        write_packed(&mut feature, 4, &geometry);
+        self.features.push(feature);
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut layer = Vec::new();
+        write_varint_field(&mut layer, 15, 2);
+        write_bytes(&mut layer, 1, self.name.as_bytes());
+        for feature in &self.features {
+            write_bytes(&mut layer, 2, feature);
+        }
+        for key in &self.keys {
+            write_bytes(&mut layer, 3, key.as_bytes());
+        }
+        for value in &self.values {
+            let mut encoded = Vec::new();
+            write_bytes(&mut encoded, 1, value.as_bytes());
+            write_bytes(&mut layer, 4, &encoded);
+        }
+        write_varint_field(&mut layer, 5, u64::from(EXTENT));
+        layer
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_varint_field(out: &mut Vec<u8>, field: u32, value: u64) {
+    write_varint(out, u64::from(field << 3));
+    write_varint(out, value);
+}
+
+fn write_bytes(out: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_varint(out, u64::from((field << 3) | 2));
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+fn write_packed(out: &mut Vec<u8>, field: u32, values: &[u32]) {
+    let mut packed = Vec::new();
+    for &value in values {
+        write_varint(&mut packed, u64::from(value));
+    }
+    write_bytes(out, field, &packed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimal protobuf reader returning (field, wire value) pairs
+    #[cfg(feature = "embedded")]
+    fn read_fields(mut buf: &[u8]) -> Vec<(u32, Result<u64, Vec<u8>>)> {
+        fn varint(buf: &mut &[u8]) -> u64 {
+            let mut value = 0;
+            let mut shift = 0;
+            loop {
+                let byte = buf[0];
+                *buf = &buf[1..];
+                value |= u64::from(byte & 0x7f) << shift;
+                if byte < 0x80 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+
+        let mut fields = Vec::new();
+        while !buf.is_empty() {
+            let key = varint(&mut buf);
+            let field = (key >> 3) as u32;
+            if key & 0x7 == 2 {
+                let len = varint(&mut buf) as usize;
+                fields.push((field, Err(buf[..len].to_vec())));
+                buf = &buf[len..];
+            } else {
+                fields.push((field, Ok(varint(&mut buf))));
+            }
+        }
+        fields
+    }
+
+    // Layer names and their feature codes
+    #[cfg(feature = "embedded")]
+    fn decode_tile(tile: &[u8]) -> Vec<(String, Vec<String>)> {
+        read_fields(tile)
+            .into_iter()
+            .map(|(_, layer)| {
+                let layer = read_fields(&layer.unwrap_err());
+                let mut name = String::new();
+                let mut values = Vec::new();
+                let mut code_tags = Vec::new();
+                for (field, value) in layer {
+                    match field {
+                        1 => name = String::from_utf8(value.unwrap_err()).unwrap(),
+                        2 => {
+                            let feature = read_fields(&value.unwrap_err());
+                            let (_, tags) = feature.iter().find(|(f, _)| *f == 2).unwrap();
+                            let tags = tags.clone().unwrap_err();
+                            assert_eq!(tags[0], 0, "code is the first tag");
+                            code_tags.push(tags[1] as usize);
+                        }
+                        4 => {
+                            let value = read_fields(&value.unwrap_err());
+                            values
+                                .push(String::from_utf8(value[0].1.clone().unwrap_err()).unwrap());
+                        }
+                        _ => {}
+                    }
+                }
+                let codes = code_tags.iter().map(|&i| values[i].clone()).collect();
+                (name, codes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_encode_square() {
+        let mut geometry = Vec::new();
+        // A square covering the upper left quarter of tile 1/0/0
+        let square = vec![vec![
+            [-180.0, 0.0],
+            [0.0, 0.0],
+            [0.0, 85.051_128_78],
+            [-180.0, 85.051_128_78],
+            [-180.0, 0.0],
+        ]];
+        encode_polygon(1, 0, 0, &square, &mut [0, 0], &mut geometry);
+        assert_eq!(
+            geometry,
+            vec![
+                command(CMD_MOVE_TO, 1),
+                zigzag(0),
+                zigzag(0),
+                command(CMD_LINE_TO, 3),
+                zigzag(4096),
+                zigzag(0),
+                zigzag(0),
+                zigzag(4096),
+                zigzag(-4096),
+                zigzag(0),
+                command(CMD_CLOSE_PATH, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tile_bounds_and_covering() {
+        let [west, south, east, north] = tile_bounds(0, 0, 0).unwrap();
+        assert_eq!((west, east), (-180.0, 180.0));
+        assert!((north - 85.051_128_78).abs() < 1e-6 && (south + 85.051_128_78).abs() < 1e-6);
+
+        let tiles: Vec<(u32, u32)> = tiles_covering([139.7, 35.6, 139.8, 35.7], 10)
+            .unwrap()
+            .collect();
+        assert_eq!(tiles, vec![(909, 403)]);
+    }
+
+    #[test]
+    fn test_invalid_tiles() {
+        assert!(tile_bounds(MAX_ZOOM, (1 << MAX_ZOOM) - 1, 0).is_ok());
+        assert!(tile_bounds(MAX_ZOOM + 1, 0, 0).is_err());
+        assert!(tile_bounds(40, 0, 0).is_err());
+        assert!(tile_bounds(0, 1, 0).is_err());
+        assert!(tile_bounds(10, 0, 1024).is_err());
+        assert!(tile_bounds(31, u32::MAX, 0).is_err());
+        assert!(tiles_covering([139.7, 35.6, 139.8, 35.7], MAX_ZOOM).is_ok());
+        assert!(tiles_covering([139.7, 35.6, 139.8, 35.7], MAX_ZOOM + 1).is_err());
+        assert!(tiles_covering([139.7, 35.6, 139.8, 35.7], 32).is_err());
+
+        let reverse_jp = ReverseJp::new();
+        assert!(reverse_jp.vector_tile(255, 0, 0).is_err());
+        assert!(reverse_jp.vector_tile(2, 4, 0).is_err());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_vector_tile_tokyo() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+
+        let tile = reverse_jp.vector_tile(10, 909, 403).unwrap();
+        let layers = decode_tile(&tile);
+        assert_eq!(layers[0].0, CLASS10S_LAYER);
+        assert_eq!(layers[1].0, CLASS20S_LAYER);
+        assert!(layers[0].1.contains(&"130010".to_string()));
+        assert!(layers[1].1.contains(&"1310100".to_string()));
+        assert!(!layers[1].1.contains(&"2710000".to_string()));
+
+        // Middle of the Pacific
+        let layers = decode_tile(&reverse_jp.vector_tile(10, 100, 400).unwrap());
+        assert!(layers.iter().all(|(_, codes)| codes.is_empty()));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_vector_tile_skips_other_levels() {
+        let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let area = crate::area::AreaData::with_embedded_data().unwrap();
+        reverse_jp
+            .add_dissolved_layer(crate::dissolve::by_prefecture)
+            .unwrap();
+        reverse_jp
+            .add_dissolved_layer(crate::dissolve::by_office(&area))
+            .unwrap();
+
+        let layers = decode_tile(&reverse_jp.vector_tile(10, 909, 403).unwrap());
+        assert_eq!(layers.len(), 2);
+        assert!(layers[0].1.contains(&"130010".to_string()));
+        assert!(!layers[0].1.contains(&"130000".to_string()));
+        assert!(
+            layers
+                .iter()
+                .flat_map(|(_, codes)| codes)
+                .all(|c| c != "13")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Prefectures (都道府県) of Japan, with their JIS X 0401 and ISO 3166-2 codes.
+//!
+//! Every JMA area code starts with the two-digit JIS prefecture number, so the
+//! prefecture of any lookup result can be resolved from its code alone.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::ReverseJp;
+//!
+//! let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//! for props in reverse_jp.find_properties(139.7670, 35.6812) {
+//!     let prefecture = props.prefecture().unwrap();
+//!     assert_eq!(prefecture.iso_code, "JP-13");
+//!     assert_eq!(prefecture.en_name, "Tokyo");
+//! }
+//! # }
+//! ```
+
+use serde::Serialize;
+
+use crate::Properties;
+
+/// A prefecture of Japan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Prefecture {
+    /// JIS X 0401 code, e.g. `13`
+    pub code: &'static str,
+    /// ISO 3166-2 code, e.g. `JP-13`
+    #[serde(rename = "isoCode")]
+    pub iso_code: &'static str,
+    /// Japanese name, e.g. `東京都`
+    pub name: &'static str,
+    /// English name, e.g. `Tokyo`
+    #[serde(rename = "enName")]
+    pub en_name: &'static str,
+}
+
+macro_rules! prefectures {
+    ($(($code:literal, $name:literal, $en_name:literal)),* $(,)?) => {
+        /// All 47 prefectures, ordered by JIS code
+        pub const PREFECTURES: [Prefecture; 47] = [$(Prefecture {
+            code: $code,
+            iso_code: concat!("JP-", $code),
+            name: $name,
+            en_name: $en_name,
+        }),*];
+    };
+}
+
+prefectures![
+    ("01", "北海道", "Hokkaido"),
+    ("02", "青森県", "Aomori"),
+    ("03", "岩手県", "Iwate"),
+    ("04", "宮城県", "Miyagi"),
+    ("05", "秋田県", "Akita"),
+    ("06", "山形県", "Yamagata"),
+    ("07", "福島県", "Fukushima"),
+    ("08", "茨城県", "Ibaraki"),
+    ("09", "栃木県", "Tochigi"),
+    ("10", "群馬県", "Gunma"),
+    ("11", "埼玉県", "Saitama"),
+    ("12", "千葉県", "Chiba"),
+    ("13", "東京都", "Tokyo"),
+    ("14", "神奈川県", "Kanagawa"),
+    ("15", "新潟県", "Niigata"),
+    ("16", "富山県", "Toyama"),
+    ("17", "石川県", "Ishikawa"),
+    ("18", "福井県", "Fukui"),
+    ("19", "山梨県", "Yamanashi"),
+    ("20", "長野県", "Nagano"),
+    ("21", "岐阜県", "Gifu"),
+    ("22", "静岡県", "Shizuoka"),
+    ("23", "愛知県", "Aichi"),
+    ("24", "三重県", "Mie"),
+    ("25", "滋賀県", "Shiga"),
+    ("26", "京都府", "Kyoto"),
+    ("27", "大阪府", "Osaka"),
+    ("28", "兵庫県", "Hyogo"),
+    ("29", "奈良県", "Nara"),
+    ("30", "和歌山県", "Wakayama"),
+    ("31", "鳥取県", "Tottori"),
+    ("32", "島根県", "Shimane"),
+    ("33", "岡山県", "Okayama"),
+    ("34", "広島県", "Hiroshima"),
+    ("35", "山口県", "Yamaguchi"),
+    ("36", "徳島県", "Tokushima"),
+    ("37", "香川県", "Kagawa"),
+    ("38", "愛媛県", "Ehime"),
+    ("39", "高知県", "Kochi"),
+    ("40", "福岡県", "Fukuoka"),
+    ("41", "佐賀県", "Saga"),
+    ("42", "長崎県", "Nagasaki"),
+    ("43", "熊本県", "Kumamoto"),
+    ("44", "大分県", "Oita"),
+    ("45", "宮崎県", "Miyazaki"),
+    ("46", "鹿児島県", "Kagoshima"),
+    ("47", "沖縄県", "Okinawa"),
+];
+
+/// Find the prefecture an area code belongs to
+///
+/// Accepts JIS prefecture codes (`13`), ISO 3166-2 codes (`JP-13`) and any
+/// JMA or JIS area code starting with the prefecture number (`130010`,
+/// `1310100`, `131016`).
+pub fn prefecture(code: &str) -> Option<&'static Prefecture> {
+    let code = code.strip_prefix("JP-").unwrap_or(code);
+    let number: usize = code.get(..2)?.parse().ok()?;
+    if !code.as_bytes()[..2].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    PREFECTURES.get(number.checked_sub(1)?)
+}
+
+impl Properties {
+    /// The prefecture this region belongs to, from the prefix of its code
+    pub fn prefecture(&self) -> Option<&'static Prefecture> {
+        prefecture(&self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        for (i, prefecture) in PREFECTURES.iter().enumerate() {
+            assert_eq!(prefecture.code, format!("{:02}", i + 1));
+            assert_eq!(prefecture.iso_code, format!("JP-{:02}", i + 1));
+        }
+    }
+
+    #[test]
+    fn test_prefecture() {
+        assert_eq!(prefecture("13").unwrap().name, "東京都");
+        assert_eq!(prefecture("JP-27").unwrap().en_name, "Osaka");
+        assert_eq!(prefecture("1310100").unwrap().iso_code, "JP-13");
+        assert_eq!(prefecture("016010").unwrap().name, "北海道");
+        assert_eq!(prefecture("472000").unwrap().name, "沖縄県");
+        assert!(prefecture("00").is_none());
+        assert!(prefecture("48").is_none());
+        assert!(prefecture("+1").is_none());
+        assert!(prefecture("hoppo").is_none());
+        assert!(prefecture("").is_none());
+    }
+}
@@ -0,0 +1,310 @@
+//! Search the regions of `area.json` by name, English name or reading.
+//!
+//! Queries and names are normalised before matching: full-width characters
+//! are folded to half-width (and half-width katakana to full-width),
+//! katakana to hiragana, and both kana and romaji to the same Hepburn form,
+//! so that `渋谷`, `しぶや`, `ｼﾌﾞﾔ`, `shibuya` and `sibuya` all find 渋谷区.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::search::{SearchIndex, SearchMode};
+//!
+//! let index = SearchIndex::with_embedded_data().unwrap();
+//! let results = index.search("shibuya", SearchMode::Prefix);
+//! assert_eq!(results[0].name, "渋谷区");
+//! assert_eq!(results[0].hierarchy[0].code, "130011");
+//! # }
+//! ```
+
+use serde::Serialize;
+
+use crate::area::AreaData;
+use crate::kana;
+use crate::{AreaCode, AreaLevel, Properties};
+
+/// How a query is matched against names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// The whole name must match
+    Exact,
+    /// The name must start with the query
+    Prefix,
+    /// The name must start with the query, allowing one typo every four characters
+    Fuzzy,
+}
+
+/// A region matching a search query
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub code: AreaCode,
+    pub level: AreaLevel,
+    pub name: String,
+    #[serde(rename = "enName")]
+    pub en_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kana: Option<String>,
+    /// Number of edits between the query and the matched name, 0 unless fuzzy
+    pub distance: usize,
+    /// Ancestors of the region, from the parent up to the center
+    pub hierarchy: Vec<Properties>,
+}
+
+struct Entry {
+    level: AreaLevel,
+    code: String,
+    name: Vec<char>,
+    roman: Vec<Vec<char>>,
+}
+
+/// Index over the names of every area of `area.json`
+pub struct SearchIndex {
+    area: AreaData,
+    entries: Vec<Entry>,
+}
+
+/// Width-fold, lowercase and convert katakana to hiragana
+fn normalize(s: &str) -> String {
+    let folded: String = kana::fold_width(s)
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'ā' => 'a',
+            'ī' => 'i',
+            'ū' => 'u',
+            'ē' => 'e',
+            'ō' => 'o',
+            _ => c,
+        })
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '\'' | '.' | ',' | '・'))
+        .collect();
+    kana::to_hiragana(&folded)
+}
+
+/// Romaji with long vowels collapsed, for comparing readings
+fn roman_key(romaji: &str) -> Vec<char> {
+    let mut key: Vec<char> = Vec::new();
+    for c in romaji.chars().filter(char::is_ascii_alphanumeric) {
+        let c = c.to_ascii_lowercase();
+        if matches!((key.last(), c), (Some('o'), 'o' | 'u') | (Some('u'), 'u')) {
+            continue;
+        }
+        key.push(c);
+    }
+    key
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3041}'..='\u{3096}' | 'ー')
+}
+
+/// Edit distance between the query and the closest prefix of the key
+fn prefix_distance(query: &[char], key: &[char]) -> usize {
+    let mut row: Vec<usize> = (0..=key.len()).collect();
+    for (i, q) in query.iter().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, k) in key.iter().enumerate() {
+            let substitution = previous + usize::from(q != k);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row.into_iter().min().unwrap_or(0)
+}
+
+/// Rank of a match, lower is better, as (kind, distance, key length)
+fn match_key(query: &[char], key: &[char], mode: SearchMode) -> Option<(u8, usize, usize)> {
+    if query.is_empty() || key.is_empty() {
+        return None;
+    }
+    if key == query {
+        return Some((0, 0, key.len()));
+    }
+    if mode == SearchMode::Exact {
+        return None;
+    }
+    if key.starts_with(query) {
+        return Some((1, 0, key.len()));
+    }
+    if mode == SearchMode::Fuzzy {
+        let distance = prefix_distance(query, key);
+        if distance <= query.len() / 4 {
+            return Some((2, distance, key.len()));
+        }
+    }
+    None
+}
+
+impl SearchIndex {
+    /// Build an index over every level of an area hierarchy
+    pub fn new(area: AreaData) -> Self {
+        let mut entries = Vec::new();
+        for level in [
+            AreaLevel::Center,
+            AreaLevel::Office,
+            AreaLevel::Class10,
+            AreaLevel::Class15,
+            AreaLevel::Class20,
+        ] {
+            let mut codes: Vec<(&String, _)> = area.areas(level).into_iter().flatten().collect();
+            codes.sort_by_key(|(code, _)| *code);
+            for (code, entry) in codes {
+                let mut roman = vec![roman_key(&entry.en_name)];
+                if let Some(kana) = &entry.kana {
+                    roman.push(roman_key(&kana::to_hepburn(kana)));
+                }
+                entries.push(Entry {
+                    level,
+                    code: code.clone(),
+                    name: normalize(&entry.name).chars().collect(),
+                    roman,
+                });
+            }
+        }
+        SearchIndex { area, entries }
+    }
+
+    /// Build an index over the area hierarchy embedded in the crate
+    #[cfg(feature = "embedded")]
+    pub fn with_embedded_data() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::new(AreaData::with_embedded_data()?))
+    }
+
+    /// Search areas by Japanese name, English name or reading
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Text in kanji, kana (full or half-width) or romaji
+    /// * `mode` - How the query is matched against names
+    ///
+    /// # Returns
+    ///
+    /// Matching areas, exact matches first, then prefix and fuzzy matches,
+    /// shorter names first
+    pub fn search(&self, query: &str, mode: SearchMode) -> Vec<SearchResult> {
+        let text = normalize(query);
+        let query_name: Vec<char> = text.chars().collect();
+        let query_roman = if !text.is_empty() && text.chars().all(is_kana) {
+            Some(roman_key(&kana::to_hepburn(&text)))
+        } else if !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric()) {
+            let reading = kana::from_romaji(&text).map(|k| kana::to_hepburn(&k));
+            Some(roman_key(reading.as_deref().unwrap_or(&text)))
+        } else {
+            None
+        };
+
+        let mut matches: Vec<((u8, usize, usize), &Entry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let by_name = match_key(&query_name, &entry.name, mode);
+                let by_roman = query_roman
+                    .iter()
+                    .flat_map(|query| entry.roman.iter().map(|key| match_key(query, key, mode)));
+                std::iter::once(by_name)
+                    .chain(by_roman)
+                    .flatten()
+                    .min()
+                    .map(|rank| (rank, entry))
+            })
+            .collect();
+        matches.sort_by_key(|(rank, _)| *rank);
+
+        matches
+            .into_iter()
+            .filter_map(|((_, distance, _), entry)| {
+                let area = self.area.areas(entry.level)?.get(&entry.code)?;
+                let hierarchy = self
+                    .area
+                    .ancestors(entry.level, &entry.code)
+                    .into_iter()
+                    .map(|(_, code, ancestor)| ancestor.properties(code))
+                    .collect();
+                Some(SearchResult {
+                    code: entry.code.as_str().into(),
+                    level: entry.level,
+                    name: area.name.clone(),
+                    en_name: area.en_name.clone(),
+                    kana: area.kana.clone(),
+                    distance,
+                    hierarchy,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "embedded")]
+    fn codes(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_prefix_distance() {
+        let chars = |s: &str| s.chars().collect::<Vec<char>>();
+        assert_eq!(prefix_distance(&chars("shibuya"), &chars("shibuyaku")), 0);
+        assert_eq!(prefix_distance(&chars("shibuyq"), &chars("shibuyaku")), 1);
+        assert_eq!(prefix_distance(&chars("saporo"), &chars("sapporoshi")), 1);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_search_shibuya() {
+        let index = SearchIndex::with_embedded_data().unwrap();
+        for query in [
+            "渋谷区",
+            "渋谷",
+            "しぶや",
+            "シブヤ",
+            "ｼﾌﾞﾔ",
+            "shibuya",
+            "Sibuya",
+            "ＳＨＩＢＵＹＡ",
+        ] {
+            let results = index.search(query, SearchMode::Prefix);
+            assert!(codes(&results).contains(&"1311300"), "{}", query);
+        }
+
+        let results = index.search("渋谷区", SearchMode::Exact);
+        assert_eq!(codes(&results), vec!["1311300"]);
+        let hierarchy: Vec<&str> = results[0]
+            .hierarchy
+            .iter()
+            .map(|p| p.code.as_str())
+            .collect();
+        assert_eq!(hierarchy, vec!["130011", "130010", "130000", "010300"]);
+        assert_eq!(results[0].level, AreaLevel::Class20);
+        assert_eq!(results[0].kana.as_deref(), Some("しぶやく"));
+
+        assert!(index.search("しぶや", SearchMode::Exact).is_empty());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_search_levels_and_long_vowels() {
+        let index = SearchIndex::with_embedded_data().unwrap();
+        let results = index.search("toukyou", SearchMode::Exact);
+        assert_eq!(results[0].code, "130000");
+        assert_eq!(results[0].level, AreaLevel::Office);
+
+        let results = index.search("oosaka", SearchMode::Prefix);
+        assert!(codes(&results).contains(&"270000"));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_search_fuzzy() {
+        let index = SearchIndex::with_embedded_data().unwrap();
+        assert!(index.search("shibuyq", SearchMode::Prefix).is_empty());
+        let results = index.search("shibuyq", SearchMode::Fuzzy);
+        assert_eq!(results[0].code, "1311300");
+        assert_eq!(results[0].distance, 1);
+
+        let results = index.search("saporo", SearchMode::Fuzzy);
+        assert!(codes(&results).contains(&"0110000"));
+    }
+}
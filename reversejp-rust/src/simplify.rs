@@ -0,0 +1,342 @@
+//! Topology-preserving simplification of region boundaries.
+//!
+//! Borders shared by neighbouring regions are simplified once, so adjacent
+//! regions still meet exactly after simplification. Simplification uses the
+//! Douglas-Peucker algorithm with a tolerance in degrees.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::{ReverseJp, simplify};
+//!
+//! let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+//!
+//! // Boundaries accurate to about one pixel at zoom 8
+//! let light = reverse_jp.simplify(simplify::tolerance_for_zoom(8)).unwrap();
+//! let props = light.find_properties(139.7670, 35.6812);
+//! assert!(props.iter().any(|p| p.code == "130010"));
+//! # }
+//! ```
+
+use std::error::Error;
+
+use crate::topology::Topology;
+use crate::{Feature, FeatureCollection, Geometry, ReverseJp};
+
+/// Simplification tolerance, in degrees, matching one pixel of a 256 pixel
+/// tile at zoom level `z`
+pub fn tolerance_for_zoom(z: u8) -> f64 {
+    360.0 / (256.0 * f64::from(1u32 << z.min(30)))
+}
+
+// Distance from `p` to the segment `a`-`b`
+fn segment_distance(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a[0] + t * dx, a[1] + t * dy);
+    ((p[0] - x).powi(2) + (p[1] - y).powi(2)).sqrt()
+}
+
+/// Simplify a polyline with the Douglas-Peucker algorithm, keeping both ends
+pub(crate) fn douglas_peucker(points: &[[f64; 2]], tolerance: f64) -> Vec<[f64; 2]> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let mut max_distance = 0.0;
+        let mut index = first;
+        for i in first + 1..last {
+            let distance = segment_distance(points[i], points[first], points[last]);
+            if distance > max_distance {
+                max_distance = distance;
+                index = i;
+            }
+        }
+        if max_distance > tolerance {
+            keep[index] = true;
+            stack.push((first, index));
+            stack.push((index, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, keep)| keep.then_some(*p))
+        .collect()
+}
+
+impl Geometry {
+    /// Simplify each ring of the geometry on its own
+    ///
+    /// Unlike [`ReverseJp::simplify`], borders shared with neighbouring regions
+    /// are not kept in sync, which is fine to draw a single region, e.g. to
+    /// highlight it on a map. Rings that would collapse are kept unsimplified.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance` - Maximum deviation from the original boundary, in
+    ///   degrees, see [`tolerance_for_zoom`]
+    pub fn simplify(&self, tolerance: f64) -> Geometry {
+        let coordinates = self
+            .coordinates
+            .iter()
+            .map(|rings| {
+                rings
+                    .iter()
+                    .map(|ring| {
+                        let simplified = douglas_peucker(ring, tolerance);
+                        if simplified.len() < 4 {
+                            ring.clone()
+                        } else {
+                            simplified
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Geometry {
+            geometry_type: self.geometry_type.clone(),
+            coordinates,
+        }
+    }
+}
+
+impl ReverseJp {
+    /// Build a new instance with simplified boundaries
+    ///
+    /// Every border is simplified once with the given tolerance, in degrees, so
+    /// neighbouring regions keep sharing the exact same border. Borders of rings
+    /// that would collapse are kept unsimplified, on both sides, so no region
+    /// disappears from lookups. Dissolved layers are kept as such.
+    ///
+    /// The simplified instance holds fewer vertices, making it lighter to
+    /// export and faster to query when metre-level accuracy is not needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance` - Maximum deviation from the original boundaries, in
+    ///   degrees, see [`tolerance_for_zoom`]
+    pub fn simplify(&self, tolerance: f64) -> Result<ReverseJp, Box<dyn Error>> {
+        let (layers, source): (Vec<u32>, Vec<Feature>) =
+            self.layered_features().into_iter().unzip();
+        let mut topology = Topology::build(&source);
+        let original = topology.arcs.clone();
+        for arc in topology.arcs.iter_mut() {
+            *arc = douglas_peucker(arc, tolerance);
+        }
+
+        // Restore the arcs of collapsed rings, which may make rings sharing
+        // them grow back too, until no ring collapses
+        loop {
+            let collapsed: Vec<usize> = topology
+                .rings
+                .iter()
+                .flatten()
+                .flatten()
+                .filter(|refs| topology.ring_coords(refs).len() < 4)
+                .flat_map(|refs| refs.iter().map(|arc_ref| arc_ref.index))
+                .filter(|&index| topology.arcs[index] != original[index])
+                .collect();
+            if collapsed.is_empty() {
+                break;
+            }
+            for index in collapsed {
+                topology.arcs[index] = original[index].clone();
+            }
+        }
+
+        let mut simplified = ReverseJp::new();
+        for ((feature, polygons), layer) in source.into_iter().zip(&topology.rings).zip(layers) {
+            let coordinates = polygons
+                .iter()
+                .map(|refs| refs.iter().map(|refs| topology.ring_coords(refs)).collect())
+                .collect();
+
+            let feature = Feature {
+                geometry: Geometry {
+                    coordinates,
+                    ..feature.geometry
+                },
+                ..feature
+            };
+            simplified.load_layer(
+                FeatureCollection {
+                    feature_type: "FeatureCollection".to_string(),
+                    features: vec![feature],
+                },
+                layer,
+            )?;
+        }
+        Ok(simplified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::topology::tests::feature;
+
+    fn vertex_count(reverse_jp: &ReverseJp) -> usize {
+        reverse_jp
+            .features()
+            .iter()
+            .flat_map(|f| f.geometry.coordinates.iter().flatten())
+            .map(|ring| ring.len())
+            .sum()
+    }
+
+    #[test]
+    fn test_douglas_peucker() {
+        let line = [[0.0, 0.0], [1.0, 0.1], [2.0, -0.1], [3.0, 5.0], [4.0, 6.0]];
+        assert_eq!(
+            douglas_peucker(&line, 0.5),
+            vec![[0.0, 0.0], [2.0, -0.1], [3.0, 5.0], [4.0, 6.0]]
+        );
+        assert_eq!(douglas_peucker(&line, 10.0), vec![[0.0, 0.0], [4.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_shared_border_stays_shared() {
+        // Two squares sharing a jagged border around x = 1
+        let border = [
+            [1.0, 0.0],
+            [1.002, 0.25],
+            [1.2, 0.5],
+            [0.998, 0.75],
+            [1.0, 1.0],
+        ];
+        let mut left = vec![[0.0, 0.0]];
+        left.extend(border);
+        left.extend([[0.0, 1.0], [0.0, 0.0]]);
+        let mut right = vec![[1.0, 1.0], [2.0, 1.0], [2.0, 0.0]];
+        right.extend(border);
+
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp
+            .process_feature_collection(FeatureCollection {
+                feature_type: "FeatureCollection".to_string(),
+                features: vec![feature("left", vec![left]), feature("right", vec![right])],
+            })
+            .unwrap();
+
+        let simplified = reverse_jp.simplify(0.1).unwrap();
+        assert!(vertex_count(&simplified) < vertex_count(&reverse_jp));
+
+        let border_of = |code: &str| -> Vec<[f64; 2]> {
+            let mut points: Vec<[f64; 2]> = simplified.geometry(code).unwrap().coordinates[0][0]
+                .iter()
+                .filter(|c| c[0] > 0.5 && c[0] < 1.5)
+                .copied()
+                .collect();
+            points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            points.dedup();
+            points
+        };
+        // The small wiggles are gone, the large one is kept on both sides
+        assert_eq!(border_of("left"), border_of("right"));
+        assert_eq!(border_of("left"), vec![[1.0, 0.0], [1.0, 1.0], [1.2, 0.5]]);
+    }
+
+    #[test]
+    fn test_collapsed_ring_keeps_shared_border() {
+        // A small region against a large one, with a slightly bent border
+        let mut large = vec![[0.0, 0.0], [1.0, 0.0], [1.0, 0.4], [1.001, 0.5], [1.0, 0.6]];
+        large.extend([[1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]);
+        let small = vec![
+            [1.0, 0.4],
+            [1.01, 0.4],
+            [1.01, 0.6],
+            [1.0, 0.6],
+            [1.001, 0.5],
+            [1.0, 0.4],
+        ];
+
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp
+            .process_feature_collection(FeatureCollection {
+                feature_type: "FeatureCollection".to_string(),
+                features: vec![feature("large", vec![large]), feature("small", vec![small])],
+            })
+            .unwrap();
+
+        // The small region would collapse, so its whole boundary is kept,
+        // including the border on the side of the large region
+        let simplified = reverse_jp.simplify(0.1).unwrap();
+        let small = &simplified.geometry("small").unwrap().coordinates[0][0];
+        assert_eq!(small.len(), 6);
+        let large = &simplified.geometry("large").unwrap().coordinates[0][0];
+        assert!(large.contains(&[1.001, 0.5]));
+        assert_eq!(large.len(), 8);
+
+        let codes = |lon, lat| -> Vec<String> {
+            simplified
+                .find_properties(lon, lat)
+                .into_iter()
+                .map(|p| p.code.into())
+                .collect()
+        };
+        assert_eq!(codes(1.005, 0.5), vec!["small"]);
+        assert_eq!(codes(1.0005, 0.5), vec!["large"]);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_simplify_keeps_layers() {
+        let mut reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        reverse_jp
+            .add_dissolved_layer(crate::dissolve::by_prefecture)
+            .unwrap();
+        let simplified = reverse_jp.simplify(tolerance_for_zoom(6)).unwrap();
+
+        assert_eq!(simplified.layers, reverse_jp.layers);
+        assert_eq!(simplified.regions(), reverse_jp.regions());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_simplify_geometry() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let geometry = reverse_jp.geometry("011000").unwrap();
+        let simplified = geometry.simplify(tolerance_for_zoom(8));
+
+        let count = |g: &Geometry| g.coordinates.iter().flatten().map(Vec::len).sum::<usize>();
+        assert!(count(&simplified) < count(&geometry));
+        assert_eq!(simplified.coordinates.len(), geometry.coordinates.len());
+        for ring in simplified.coordinates.iter().flatten() {
+            assert!(ring.len() >= 4);
+            assert_eq!(ring.first(), ring.last());
+        }
+        assert_eq!(geometry.simplify(0.0), geometry);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_simplify_embedded() {
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        let simplified = reverse_jp.simplify(tolerance_for_zoom(8)).unwrap();
+
+        assert_eq!(simplified.polygons.len(), reverse_jp.polygons.len());
+        assert!(vertex_count(&simplified) * 2 < vertex_count(&reverse_jp));
+
+        // The Imperial Palace, well inside 千代田区
+        let codes: Vec<String> = simplified
+            .find_properties(139.7528, 35.6852)
+            .into_iter()
+            .map(|p| p.code.into())
+            .collect();
+        assert!(codes.contains(&"130010".to_string()));
+        assert!(codes.contains(&"1310100".to_string()));
+    }
+}
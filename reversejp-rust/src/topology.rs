@@ -0,0 +1,262 @@
+// Arc topology of the loaded features, in the spirit of TopoJSON.
+//
+// Every ring is cut at junctions, vertices where rings stop sharing a border,
+// into arcs. A border shared by two regions becomes a single arc referenced by
+// both rings, once in each direction, so operations applied per arc keep
+// neighbouring regions consistent.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::Feature;
+
+type Key = (u64, u64);
+
+fn key(coord: [f64; 2]) -> Key {
+    // Normalise -0.0 so that it shares a key with 0.0
+    ((coord[0] + 0.0).to_bits(), (coord[1] + 0.0).to_bits())
+}
+
+/// A reference from a ring to an arc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ArcRef {
+    pub index: usize,
+    pub reversed: bool,
+}
+
+pub(crate) struct Topology {
+    /// Arc coordinates, each arc starts and ends at a junction
+    pub arcs: Vec<Vec<[f64; 2]>>,
+    /// Arcs of every ring, indexed by feature, polygon and ring
+    pub rings: Vec<Vec<Vec<Vec<ArcRef>>>>,
+}
+
+// Ring vertices without consecutive duplicates and without the closing vertex
+fn open_ring(ring: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    let mut points: Vec<[f64; 2]> = Vec::with_capacity(ring.len());
+    for &coord in ring {
+        if points.last().map(|&p| key(p)) != Some(key(coord)) {
+            points.push(coord);
+        }
+    }
+    while points.len() > 1 && key(points[0]) == key(points[points.len() - 1]) {
+        points.pop();
+    }
+    points
+}
+
+impl Topology {
+    pub fn build(features: &[Feature]) -> Self {
+        let rings: Vec<Vec<Vec<Vec<[f64; 2]>>>> = features
+            .iter()
+            .map(|f| {
+                f.geometry
+                    .coordinates
+                    .iter()
+                    .map(|polygon| polygon.iter().map(|ring| open_ring(ring)).collect())
+                    .collect()
+            })
+            .collect();
+
+        // A vertex is a junction when it is seen with different neighbours
+        let mut neighbours: HashMap<Key, (Key, Key)> = HashMap::new();
+        let mut junctions: HashSet<Key> = HashSet::new();
+        for ring in rings.iter().flatten().flatten() {
+            let n = ring.len();
+            for i in 0..n {
+                let prev = key(ring[(i + n - 1) % n]);
+                let next = key(ring[(i + 1) % n]);
+                let pair = if prev <= next {
+                    (prev, next)
+                } else {
+                    (next, prev)
+                };
+                let vertex = key(ring[i]);
+                match neighbours.get(&vertex) {
+                    Some(&seen) if seen != pair => {
+                        junctions.insert(vertex);
+                    }
+                    Some(_) => {}
+                    None => {
+                        neighbours.insert(vertex, pair);
+                    }
+                }
+            }
+        }
+
+        let mut topology = Topology {
+            arcs: Vec::new(),
+            rings: Vec::new(),
+        };
+        let mut arc_index: HashMap<Vec<Key>, usize> = HashMap::new();
+
+        for feature in rings {
+            let mut feature_refs = Vec::new();
+            for polygon in feature {
+                let mut polygon_refs = Vec::new();
+                for ring in polygon {
+                    let refs = topology.split_ring(&ring, &junctions, &mut arc_index);
+                    polygon_refs.push(refs);
+                }
+                feature_refs.push(polygon_refs);
+            }
+            topology.rings.push(feature_refs);
+        }
+
+        topology
+    }
+
+    fn split_ring(
+        &mut self,
+        ring: &[[f64; 2]],
+        junctions: &HashSet<Key>,
+        arc_index: &mut HashMap<Vec<Key>, usize>,
+    ) -> Vec<ArcRef> {
+        let n = ring.len();
+        if n == 0 {
+            return vec![];
+        }
+
+        let cuts: Vec<usize> = (0..n)
+            .filter(|&i| junctions.contains(&key(ring[i])))
+            .collect();
+
+        let mut arcs: Vec<Vec<[f64; 2]>> = Vec::new();
+        if cuts.is_empty() {
+            // A closed arc, rotated to start at its smallest vertex so that
+            // the same ring traversed from elsewhere maps to the same arc
+            let start = (0..n).min_by_key(|&i| key(ring[i])).unwrap();
+            arcs.push((0..=n).map(|i| ring[(start + i) % n]).collect());
+        } else {
+            for (c, &from) in cuts.iter().enumerate() {
+                let to = cuts[(c + 1) % cuts.len()];
+                let len = if to > from { to - from } else { to + n - from };
+                arcs.push((0..=len).map(|i| ring[(from + i) % n]).collect());
+            }
+        }
+
+        let mut refs = Vec::with_capacity(arcs.len());
+        for arc in arcs {
+            let forward: Vec<Key> = arc.iter().map(|&c| key(c)).collect();
+            let backward: Vec<Key> = forward.iter().rev().copied().collect();
+            let (canonical, reversed) = if forward <= backward {
+                (forward, false)
+            } else {
+                (backward, true)
+            };
+
+            let index = *arc_index.entry(canonical).or_insert_with(|| {
+                let mut coords = arc;
+                if reversed {
+                    coords.reverse();
+                }
+                self.arcs.push(coords);
+                self.arcs.len() - 1
+            });
+            refs.push(ArcRef { index, reversed });
+        }
+        refs
+    }
+
+    /// Rebuild a closed ring from its arcs
+    pub fn ring_coords(&self, refs: &[ArcRef]) -> Vec<[f64; 2]> {
+        let mut coords: Vec<[f64; 2]> = Vec::new();
+        for arc_ref in refs {
+            let arc = &self.arcs[arc_ref.index];
+            let mut points: Box<dyn Iterator<Item = &[f64; 2]>> = if arc_ref.reversed {
+                Box::new(arc.iter().rev())
+            } else {
+                Box::new(arc.iter())
+            };
+            // Consecutive arcs share their junction vertex
+            if !coords.is_empty() {
+                points.next();
+            }
+            coords.extend(points);
+        }
+        if let (Some(&first), Some(&last)) = (coords.first(), coords.last())
+            && key(first) != key(last)
+        {
+            coords.push(first);
+        }
+        coords
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{Geometry, Properties};
+
+    pub(crate) fn feature(code: &str, rings: Vec<Vec<[f64; 2]>>) -> Feature {
+        Feature {
+            feature_type: "Feature".to_string(),
+            geometry: Geometry {
+                geometry_type: "MultiPolygon".to_string(),
+                coordinates: vec![rings],
+            },
+            properties: Properties {
+                code: code.into(),
+                name: code.to_string(),
+                en_name: String::new(),
+                kana: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_shared_border_is_one_arc() {
+        // Two squares sharing the x = 1 edge, drawn in opposite directions
+        let left = feature(
+            "left",
+            vec![vec![
+                [0.0, 0.0],
+                [1.0, 0.0],
+                [1.0, 1.0],
+                [0.0, 1.0],
+                [0.0, 0.0],
+            ]],
+        );
+        let right = feature(
+            "right",
+            vec![vec![
+                [1.0, 0.0],
+                [2.0, 0.0],
+                [2.0, 1.0],
+                [1.0, 1.0],
+                [1.0, 0.0],
+            ]],
+        );
+        let topology = Topology::build(&[left.clone(), right.clone()]);
+
+        let left_refs = &topology.rings[0][0][0];
+        let right_refs = &topology.rings[1][0][0];
+        assert_eq!(left_refs.len(), 2);
+        assert_eq!(right_refs.len(), 2);
+        let shared: Vec<&ArcRef> = left_refs
+            .iter()
+            .filter(|l| right_refs.iter().any(|r| r.index == l.index))
+            .collect();
+        assert_eq!(shared.len(), 1);
+        assert_eq!(topology.arcs.len(), 3);
+
+        // Rings are rebuilt with the same vertices
+        let rebuilt = topology.ring_coords(left_refs);
+        assert_eq!(rebuilt.len(), 5);
+        for coord in &left.geometry.coordinates[0][0] {
+            assert!(rebuilt.contains(coord));
+        }
+    }
+
+    #[test]
+    fn test_unshared_ring_is_closed_arc() {
+        let island = feature(
+            "island",
+            vec![vec![[5.0, 5.0], [6.0, 5.0], [6.0, 6.0], [5.0, 5.0]]],
+        );
+        let topology = Topology::build(&[island]);
+        assert_eq!(topology.arcs.len(), 1);
+        let ring = topology.ring_coords(&topology.rings[0][0][0]);
+        assert_eq!(ring.len(), 4);
+        assert_eq!(ring.first(), ring.last());
+    }
+}
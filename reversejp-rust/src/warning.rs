@@ -0,0 +1,209 @@
+//! JMA warning elements (警報・注意報の種類) from `warning.json`.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::warning::{WarningCatalog, WarningElement};
+//!
+//! let catalog = WarningCatalog::with_embedded_data().unwrap();
+//! let flood = catalog.get(WarningElement::Flood).unwrap();
+//! assert_eq!(flood.name, "洪水");
+//! assert_eq!(catalog.find("Heavy Snow").unwrap().element, WarningElement::Snow);
+//! # }
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A kind of weather warning, identified by its `value` in `warning.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningElement {
+    /// Every element, used as a filter
+    All,
+    Inundation,
+    Landslide,
+    Flood,
+    Wind,
+    WindSnow,
+    Snow,
+    Wave,
+    Tide,
+    Thunder,
+    SnowMelting,
+    Fog,
+    Dry,
+    Avalanche,
+    Cold,
+    Frost,
+    IceAccretion,
+    SnowAccretion,
+}
+
+impl WarningElement {
+    /// Every element, in the order of `warning.json`
+    pub const ELEMENTS: [WarningElement; 18] = [
+        WarningElement::All,
+        WarningElement::Inundation,
+        WarningElement::Landslide,
+        WarningElement::Flood,
+        WarningElement::Wind,
+        WarningElement::WindSnow,
+        WarningElement::Snow,
+        WarningElement::Wave,
+        WarningElement::Tide,
+        WarningElement::Thunder,
+        WarningElement::SnowMelting,
+        WarningElement::Fog,
+        WarningElement::Dry,
+        WarningElement::Avalanche,
+        WarningElement::Cold,
+        WarningElement::Frost,
+        WarningElement::IceAccretion,
+        WarningElement::SnowAccretion,
+    ];
+
+    /// The `value` of the element in `warning.json`, e.g. `wind_snow`
+    pub fn value(self) -> &'static str {
+        match self {
+            WarningElement::All => "all",
+            WarningElement::Inundation => "inundation",
+            WarningElement::Landslide => "landslide",
+            WarningElement::Flood => "flood",
+            WarningElement::Wind => "wind",
+            WarningElement::WindSnow => "wind_snow",
+            WarningElement::Snow => "snow",
+            WarningElement::Wave => "wave",
+            WarningElement::Tide => "tide",
+            WarningElement::Thunder => "thunder",
+            WarningElement::SnowMelting => "snow_melting",
+            WarningElement::Fog => "fog",
+            WarningElement::Dry => "dry",
+            WarningElement::Avalanche => "avalanche",
+            WarningElement::Cold => "cold",
+            WarningElement::Frost => "frost",
+            WarningElement::IceAccretion => "ice_accretion",
+            WarningElement::SnowAccretion => "snow_accretion",
+        }
+    }
+}
+
+impl FromStr for WarningElement {
+    type Err = Box<dyn Error>;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        WarningElement::ELEMENTS
+            .into_iter()
+            .find(|element| element.value() == value)
+            .ok_or_else(|| format!("Unknown warning element: {}", value).into())
+    }
+}
+
+impl fmt::Display for WarningElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.value())
+    }
+}
+
+/// An entry of `warning.json`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WarningElementInfo {
+    #[serde(rename = "value")]
+    pub element: WarningElement,
+    /// Japanese name, e.g. `暴風・強風`
+    pub name: String,
+    /// English name, e.g. `Storm/Gale`
+    #[serde(rename = "enName")]
+    pub en_name: String,
+}
+
+#[derive(Deserialize)]
+struct WarningList {
+    key: String,
+    values: Vec<WarningElementInfo>,
+}
+
+/// The warning elements of `warning.json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningCatalog {
+    elements: Vec<WarningElementInfo>,
+}
+
+impl WarningCatalog {
+    /// Load the warning elements embedded in the crate
+    #[cfg(feature = "embedded")]
+    pub fn with_embedded_data() -> Result<Self, Box<dyn Error>> {
+        Self::from_json(&crate::get_warning_data()?)
+    }
+
+    /// Load warning elements from the JSON of `warning.json`
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let lists: Vec<WarningList> = serde_json::from_str(json)?;
+        let elements = lists
+            .into_iter()
+            .find(|list| list.key == "elem")
+            .ok_or("No warning element list")?
+            .values;
+        Ok(WarningCatalog { elements })
+    }
+
+    /// Every element, in the order of the source data
+    pub fn elements(&self) -> &[WarningElementInfo] {
+        &self.elements
+    }
+
+    /// Get the names of an element
+    pub fn get(&self, element: WarningElement) -> Option<&WarningElementInfo> {
+        self.elements.iter().find(|info| info.element == element)
+    }
+
+    /// Find an element by its Japanese name, English name or value
+    ///
+    /// English names and values are matched case-insensitively.
+    pub fn find(&self, name: &str) -> Option<&WarningElementInfo> {
+        self.elements.iter().find(|info| {
+            info.name == name
+                || info.en_name.eq_ignore_ascii_case(name)
+                || info.element.value().eq_ignore_ascii_case(name)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_embedded_catalog() {
+        let catalog = WarningCatalog::with_embedded_data().unwrap();
+        let elements: Vec<WarningElement> = catalog.elements().iter().map(|i| i.element).collect();
+        assert_eq!(elements, WarningElement::ELEMENTS);
+        assert_eq!(
+            catalog.get(WarningElement::WindSnow).unwrap().name,
+            "暴風雪・風雪"
+        );
+        assert_eq!(catalog.find("なだれ").unwrap().en_name, "Avalanche");
+        assert_eq!(
+            catalog.find("dense fog").unwrap().element,
+            WarningElement::Fog
+        );
+        assert_eq!(
+            catalog.find("ice_accretion").unwrap().element,
+            WarningElement::IceAccretion
+        );
+        assert!(catalog.find("tornado").is_none());
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        for element in WarningElement::ELEMENTS {
+            assert_eq!(element.value().parse::<WarningElement>().unwrap(), element);
+            let json = serde_json::to_string(&element).unwrap();
+            assert_eq!(json, format!("\"{}\"", element));
+        }
+        assert!("tornado".parse::<WarningElement>().is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Well-Known Binary (WKB) encoding and decoding of region geometries.
+//!
+//! Geometries are written as little-endian 2D `MultiPolygon`s. Decoding
+//! accepts `Polygon` and `MultiPolygon` in either byte order.
+
+use std::error::Error;
+
+use crate::Geometry;
+
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOLYGON: u32 = 6;
+
+/// Encode a MultiPolygon geometry as little-endian WKB
+pub fn to_wkb(geometry: &Geometry) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.push(1);
+    out.extend_from_slice(&WKB_MULTIPOLYGON.to_le_bytes());
+    out.extend_from_slice(&(geometry.coordinates.len() as u32).to_le_bytes());
+
+    for polygon in &geometry.coordinates {
+        out.push(1);
+        out.extend_from_slice(&WKB_POLYGON.to_le_bytes());
+        out.extend_from_slice(&(polygon.len() as u32).to_le_bytes());
+        for ring in polygon {
+            out.extend_from_slice(&(ring.len() as u32).to_le_bytes());
+            for coord in ring {
+                out.extend_from_slice(&coord[0].to_le_bytes());
+                out.extend_from_slice(&coord[1].to_le_bytes());
+            }
+        }
+    }
+    out
+}
+
+/// Decode a WKB `Polygon` or `MultiPolygon` into a MultiPolygon geometry
+pub fn from_wkb(wkb: &[u8]) -> Result<Geometry, Box<dyn Error>> {
+    let mut reader = Reader {
+        input: wkb,
+        pos: 0,
+        little_endian: true,
+    };
+
+    let coordinates = match reader.header()? {
+        WKB_POLYGON => vec![reader.polygon_body()?],
+        WKB_MULTIPOLYGON => {
+            let count = reader.u32()?;
+            let mut polygons = Vec::new();
+            for _ in 0..count {
+                if reader.header()? != WKB_POLYGON {
+                    return Err("MultiPolygon WKB must only contain Polygons".into());
+                }
+                polygons.push(reader.polygon_body()?);
+            }
+            polygons
+        }
+        other => return Err(format!("Unsupported WKB geometry type: {}", other).into()),
+    };
+
+    if reader.pos != wkb.len() {
+        return Err("Unexpected trailing bytes after WKB geometry".into());
+    }
+
+    Ok(Geometry {
+        geometry_type: "MultiPolygon".to_string(),
+        coordinates,
+    })
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Box<dyn Error>> {
+        let bytes = self
+            .input
+            .get(self.pos..self.pos + N)
+            .ok_or("Unexpected end of WKB input")?;
+        self.pos += N;
+        Ok(bytes.try_into()?)
+    }
+
+    // Read the byte order marker and geometry type of the next geometry
+    fn header(&mut self) -> Result<u32, Box<dyn Error>> {
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            other => return Err(format!("Invalid WKB byte order: {}", other).into()),
+        };
+        self.u32()
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64, Box<dyn Error>> {
+        let bytes = self.take::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn polygon_body(&mut self) -> Result<Vec<Vec<[f64; 2]>>, Box<dyn Error>> {
+        let ring_count = self.u32()?;
+        let mut rings = Vec::new();
+        for _ in 0..ring_count {
+            let point_count = self.u32()?;
+            // Guard against bogus counts before allocating
+            if (point_count as usize).saturating_mul(16) > self.input.len() - self.pos {
+                return Err("Unexpected end of WKB input".into());
+            }
+            let mut ring = Vec::with_capacity(point_count as usize);
+            for _ in 0..point_count {
+                ring.push([self.f64()?, self.f64()?]);
+            }
+            rings.push(ring);
+        }
+        Ok(rings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_big_endian_polygon() {
+        let mut wkb = vec![0];
+        wkb.extend_from_slice(&WKB_POLYGON.to_be_bytes());
+        wkb.extend_from_slice(&1u32.to_be_bytes());
+        wkb.extend_from_slice(&4u32.to_be_bytes());
+        for [x, y] in [[0.0f64, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]] {
+            wkb.extend_from_slice(&x.to_be_bytes());
+            wkb.extend_from_slice(&y.to_be_bytes());
+        }
+
+        let geometry = from_wkb(&wkb).unwrap();
+        assert_eq!(
+            geometry.coordinates,
+            vec![vec![vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]]
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(from_wkb(&[]).is_err());
+        assert!(from_wkb(&[1, 1, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(from_wkb(&[1, 3, 0, 0, 0, 1, 0, 0, 0, 255, 255, 255, 255]).is_err());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_round_trip_class10s() {
+        use crate::{FeatureCollection, get_class10s_data};
+
+        let collection: FeatureCollection =
+            serde_json::from_str(&get_class10s_data().unwrap()).unwrap();
+        for feature in collection.features {
+            let decoded = from_wkb(&to_wkb(&feature.geometry)).unwrap();
+            assert_eq!(decoded, feature.geometry, "{}", feature.properties.code);
+        }
+    }
+}
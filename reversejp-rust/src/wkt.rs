@@ -0,0 +1,238 @@
+//! Well-Known Text (WKT) encoding and decoding of region geometries.
+//!
+//! Only polygonal geometries are supported: `POLYGON` and `MULTIPOLYGON`.
+//! Both are decoded into a MultiPolygon [`Geometry`], the same shape used by
+//! the GeoJSON data embedded in the crate.
+
+use std::error::Error;
+use std::fmt::Write;
+
+use crate::Geometry;
+
+/// Encode a MultiPolygon geometry as WKT
+///
+/// # Example
+///
+/// ```rust
+/// use reversejp::{Geometry, wkt};
+///
+/// let geometry = Geometry {
+///     geometry_type: "MultiPolygon".to_string(),
+///     coordinates: vec![vec![vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]],
+/// };
+/// assert_eq!(wkt::to_wkt(&geometry), "MULTIPOLYGON(((0 0,1 0,1 1,0 0)))");
+/// ```
+pub fn to_wkt(geometry: &Geometry) -> String {
+    let mut out = String::from("MULTIPOLYGON");
+    if geometry.coordinates.is_empty() {
+        out.push_str(" EMPTY");
+        return out;
+    }
+
+    out.push('(');
+    for (i, polygon) in geometry.coordinates.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push('(');
+        for (j, ring) in polygon.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            out.push('(');
+            for (k, coord) in ring.iter().enumerate() {
+                if k > 0 {
+                    out.push(',');
+                }
+                // `{}` prints the shortest representation that round-trips
+                let _ = write!(out, "{} {}", coord[0], coord[1]);
+            }
+            out.push(')');
+        }
+        out.push(')');
+    }
+    out.push(')');
+    out
+}
+
+/// Decode a WKT `POLYGON` or `MULTIPOLYGON` into a MultiPolygon geometry
+///
+/// Keywords are case-insensitive. Geometries with Z or M dimensions are rejected.
+pub fn from_wkt(wkt: &str) -> Result<Geometry, Box<dyn Error>> {
+    let mut parser = Parser {
+        input: wkt.as_bytes(),
+        pos: 0,
+    };
+
+    let keyword = parser.keyword()?;
+    let coordinates = match keyword.as_str() {
+        "POLYGON" => {
+            if parser.empty()? {
+                vec![]
+            } else {
+                vec![parser.polygon()?]
+            }
+        }
+        "MULTIPOLYGON" => {
+            if parser.empty()? {
+                vec![]
+            } else {
+                parser.list(|p| p.polygon())?
+            }
+        }
+        other => return Err(format!("Unsupported WKT geometry type: {}", other).into()),
+    };
+
+    parser.skip_whitespace();
+    if parser.pos != parser.input.len() {
+        return Err(format!("Unexpected trailing WKT input at offset {}", parser.pos).into());
+    }
+
+    Ok(Geometry {
+        geometry_type: "MultiPolygon".to_string(),
+        coordinates,
+    })
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Box<dyn Error>> {
+        match self.peek() {
+            Some(b) if b == byte => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => Err(format!("Expected '{}' at WKT offset {}", byte as char, self.pos).into()),
+        }
+    }
+
+    fn keyword(&mut self) -> Result<String, Box<dyn Error>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_alphabetic() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(format!("Expected WKT keyword at offset {}", start).into());
+        }
+        Ok(String::from_utf8_lossy(&self.input[start..self.pos]).to_ascii_uppercase())
+    }
+
+    // Consume an optional `EMPTY` marker, rejecting dimension markers such as `Z`
+    fn empty(&mut self) -> Result<bool, Box<dyn Error>> {
+        if self.peek() == Some(b'(') {
+            return Ok(false);
+        }
+        match self.keyword()?.as_str() {
+            "EMPTY" => Ok(true),
+            other => Err(format!("Unsupported WKT modifier: {}", other).into()),
+        }
+    }
+
+    // Parse a parenthesised, comma separated list
+    fn list<T, F>(&mut self, mut item: F) -> Result<Vec<T>, Box<dyn Error>>
+    where
+        F: FnMut(&mut Self) -> Result<T, Box<dyn Error>>,
+    {
+        self.expect(b'(')?;
+        let mut items = vec![item(self)?];
+        while self.peek() == Some(b',') {
+            self.pos += 1;
+            items.push(item(self)?);
+        }
+        self.expect(b')')?;
+        Ok(items)
+    }
+
+    fn polygon(&mut self) -> Result<Vec<Vec<[f64; 2]>>, Box<dyn Error>> {
+        self.list(|p| p.list(|p| p.coord()))
+    }
+
+    fn coord(&mut self) -> Result<[f64; 2], Box<dyn Error>> {
+        let x = self.number()?;
+        let y = self.number()?;
+        match self.peek() {
+            Some(b',') | Some(b')') => Ok([x, y]),
+            _ => Err(format!(
+                "Only 2D coordinates are supported, at WKT offset {}",
+                self.pos
+            )
+            .into()),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, Box<dyn Error>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.pos < self.input.len()
+            && matches!(
+                self.input[self.pos],
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'
+            )
+        {
+            self.pos += 1;
+        }
+        let token = std::str::from_utf8(&self.input[start..self.pos])?;
+        token
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid WKT number at offset {}", start).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_polygon_with_hole() {
+        let geometry =
+            from_wkt("polygon ((0 0, 10 0, 10 10, 0 10, 0 0), (4 4, 6 4, 6 6, 4 6, 4 4))").unwrap();
+        assert_eq!(geometry.geometry_type, "MultiPolygon");
+        assert_eq!(geometry.coordinates.len(), 1);
+        assert_eq!(geometry.coordinates[0].len(), 2);
+        assert_eq!(geometry.coordinates[0][1][2], [6.0, 6.0]);
+    }
+
+    #[test]
+    fn test_parse_empty_and_invalid() {
+        assert!(
+            from_wkt("MULTIPOLYGON EMPTY")
+                .unwrap()
+                .coordinates
+                .is_empty()
+        );
+        assert!(from_wkt("POINT (1 2)").is_err());
+        assert!(from_wkt("POLYGON Z ((0 0 0, 1 0 0, 1 1 0, 0 0 0))").is_err());
+        assert!(from_wkt("POLYGON ((0 0 0, 1 0 0, 1 1 0, 0 0 0))").is_err());
+        assert!(from_wkt("POLYGON ((0 0, 1 0, 1 1, 0 0)) extra").is_err());
+        assert!(from_wkt("POLYGON ((0 0, 1 0, 1 1, 0 0)").is_err());
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_round_trip_class10s() {
+        use crate::{FeatureCollection, get_class10s_data};
+
+        let collection: FeatureCollection =
+            serde_json::from_str(&get_class10s_data().unwrap()).unwrap();
+        for feature in collection.features {
+            let decoded = from_wkt(&to_wkt(&feature.geometry)).unwrap();
+            assert_eq!(decoded, feature.geometry, "{}", feature.properties.code);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Representative points of areas from `xy.json`.
+//!
+//! JMA places a point inside each center, office and class20, e.g. to label
+//! areas on maps. Class10s and class15s have no point.
+//!
+//! ```rust
+//! # #[cfg(feature = "embedded")] {
+//! use reversejp::xy::XyData;
+//!
+//! let xy = XyData::with_embedded_data().unwrap();
+//! let (longitude, latitude) = xy.point("1311300").unwrap();
+//! assert_eq!((longitude, latitude), (139.6918, 35.6717));
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::AreaLevel;
+
+/// All points of `xy.json`, as `[latitude, longitude]` keyed by code for each level
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct XyData {
+    pub centers: HashMap<String, [f64; 2]>,
+    pub offices: HashMap<String, [f64; 2]>,
+    pub class20s: HashMap<String, [f64; 2]>,
+}
+
+impl XyData {
+    /// Load the points embedded in the crate
+    #[cfg(feature = "embedded")]
+    pub fn with_embedded_data() -> Result<Self, Box<dyn Error>> {
+        Self::from_json(&crate::get_xy_data()?)
+    }
+
+    /// Load points from the JSON of `xy.json`
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The points of one level, keyed by code
+    ///
+    /// Only centers, offices and class20s have points.
+    pub fn points(&self, level: AreaLevel) -> Option<&HashMap<String, [f64; 2]>> {
+        match level {
+            AreaLevel::Center => Some(&self.centers),
+            AreaLevel::Office => Some(&self.offices),
+            AreaLevel::Class20 => Some(&self.class20s),
+            _ => None,
+        }
+    }
+
+    /// Get the point of an area of a given level
+    ///
+    /// # Returns
+    ///
+    /// The `(longitude, latitude)` of the point, or `None` if the area has none
+    pub fn get(&self, level: AreaLevel, code: &str) -> Option<(f64, f64)> {
+        let [latitude, longitude] = *self.points(level)?.get(code)?;
+        Some((longitude, latitude))
+    }
+
+    /// Get the point of an area by code alone
+    ///
+    /// Class20s are searched first, then offices and centers.
+    pub fn point(&self, code: &str) -> Option<(f64, f64)> {
+        [AreaLevel::Class20, AreaLevel::Office, AreaLevel::Center]
+            .into_iter()
+            .find_map(|level| self.get(level, code))
+    }
+}
+
+// Every test reads the embedded data
+#[cfg(all(test, feature = "embedded"))]
+mod tests {
+    use super::*;
+    use crate::ReverseJp;
+
+    #[test]
+    fn test_embedded_points() {
+        let xy = XyData::with_embedded_data().unwrap();
+        assert_eq!(
+            xy.get(AreaLevel::Office, "130000"),
+            Some((139.2929, 35.7014))
+        );
+        assert_eq!(xy.point("010300"), Some((139.1246, 36.0994)));
+        assert_eq!(xy.get(AreaLevel::Class10, "130010"), None);
+        assert_eq!(xy.point("not-a-code"), None);
+    }
+
+    #[test]
+    fn test_class20_points_are_inside() {
+        let xy = XyData::with_embedded_data().unwrap();
+        let reverse_jp = ReverseJp::with_embedded_data().unwrap();
+        for code in ["1311300", "0110000", "2710000"] {
+            let (longitude, latitude) = xy.get(AreaLevel::Class20, code).unwrap();
+            let props = reverse_jp.find_properties(longitude, latitude);
+            assert!(props.iter().any(|p| p.code == code), "{}", code);
+        }
+    }
+}
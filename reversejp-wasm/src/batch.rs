@@ -0,0 +1,140 @@
+//! Batch lookups returning region indices instead of JS objects.
+
+use js_sys::Uint32Array;
+use reversejp::{Properties, ReverseJp};
+use wasm_bindgen::prelude::*;
+
+/// The loaded regions, indexed by position for [`RegionIndices`]
+pub struct RegionTable {
+    pub properties: Vec<Properties>,
+}
+
+impl RegionTable {
+    pub fn new(reverse_jp: &ReverseJp) -> Self {
+        let properties = reverse_jp.regions().into_iter().cloned().collect();
+        Self { properties }
+    }
+}
+
+/// Offsets and region indices of a batch lookup, see [`RegionIndices`]
+#[derive(Debug, PartialEq)]
+pub struct BatchIndices {
+    pub offsets: Vec<u32>,
+    pub indices: Vec<u32>,
+}
+
+/// Regions of many points, as indices into the table returned by `regions()`.
+///
+/// The regions of point `i` are `indices[offsets[i]..offsets[i + 1]]`.
+#[wasm_bindgen]
+pub struct RegionIndices {
+    // Copied to JS once, getters return the same arrays
+    offsets: Uint32Array,
+    indices: Uint32Array,
+}
+
+impl From<BatchIndices> for RegionIndices {
+    fn from(batch: BatchIndices) -> Self {
+        Self {
+            offsets: Uint32Array::from(batch.offsets.as_slice()),
+            indices: Uint32Array::from(batch.indices.as_slice()),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RegionIndices {
+    /// Start of the regions of each point in `indices`, one more than the number of points.
+    #[wasm_bindgen(getter)]
+    pub fn offsets(&self) -> Uint32Array {
+        self.offsets.clone()
+    }
+
+    /// Region indices of all points, concatenated.
+    #[wasm_bindgen(getter)]
+    pub fn indices(&self) -> Uint32Array {
+        self.indices.clone()
+    }
+
+    /// Number of points looked up.
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> u32 {
+        self.offsets.length() - 1
+    }
+}
+
+/// Look up interleaved `[lon0, lat0, lon1, lat1, ...]` coordinates
+///
+/// Indices are positions in `regions()`, the order of [`RegionTable`].
+pub fn find_indices(reverse_jp: &ReverseJp, coordinates: &[f64]) -> Result<BatchIndices, String> {
+    if !coordinates.len().is_multiple_of(2) {
+        return Err(format!(
+            "Coordinates must be interleaved longitude/latitude pairs, got {} values",
+            coordinates.len()
+        ));
+    }
+
+    let mut offsets = Vec::with_capacity(coordinates.len() / 2 + 1);
+    let mut indices = Vec::with_capacity(coordinates.len());
+    offsets.push(0);
+    for point in coordinates.chunks_exact(2) {
+        indices.extend(
+            reverse_jp
+                .find_region_indices(point[0], point[1])
+                .into_iter()
+                .map(|idx| idx as u32),
+        );
+        offsets.push(indices.len() as u32);
+    }
+    Ok(BatchIndices { offsets, indices })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(code: &str, name: &str, x: f64, y: f64, size: f64) -> String {
+        format!(
+            r#"{{"type": "Feature", "properties": {{"code": "{}", "name": "{}"}},
+                "geometry": {{"type": "MultiPolygon", "coordinates": [[[
+                    [{x}, {y}], [{x1}, {y}], [{x1}, {y1}], [{x}, {y1}], [{x}, {y}]
+                ]]]}}}}"#,
+            code,
+            name,
+            x = x,
+            y = y,
+            x1 = x + size,
+            y1 = y + size,
+        )
+    }
+
+    #[test]
+    fn test_find_indices() {
+        // A large region containing a small one, a region elsewhere, and a
+        // region reusing the code of the small one
+        let geojson = format!(
+            r#"{{"type": "FeatureCollection", "features": [{}, {}, {}, {}]}}"#,
+            square("large", "large", 0.0, 0.0, 10.0),
+            square("small", "small", 1.0, 1.0, 1.0),
+            square("other", "other", 20.0, 0.0, 1.0),
+            square("small", "copy", 30.0, 0.0, 1.0),
+        );
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp.load_geojson(&geojson).unwrap();
+        let table = RegionTable::new(&reverse_jp);
+        let names: Vec<&str> = table.properties.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["large", "small", "other", "copy"]);
+
+        let coordinates = [1.5, 1.5, 5.0, 5.0, 50.0, 50.0, 20.5, 0.5, 30.5, 0.5];
+        let batch = find_indices(&reverse_jp, &coordinates).unwrap();
+        assert_eq!(batch.offsets, vec![0, 2, 3, 3, 4, 5]);
+        // Both regions coded `small` keep their own index
+        assert_eq!(batch.indices, vec![0, 1, 0, 2, 3]);
+
+        let empty = find_indices(&reverse_jp, &[]).unwrap();
+        assert_eq!(empty.offsets, vec![0]);
+        assert!(empty.indices.is_empty());
+
+        assert!(find_indices(&reverse_jp, &[1.5, 1.5, 5.0]).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Download a dataset with `fetch`, reporting progress as chunks arrive.
+
+use js_sys::{Function, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{ReadableStreamDefaultReader, Response};
+
+#[wasm_bindgen]
+extern "C" {
+    // The global `fetch`, available in windows and workers alike
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_str(input: &str) -> js_sys::Promise;
+}
+
+// Most memory reserved up front from a `Content-Length`, which the server may
+// get wrong; larger bodies still grow as chunks arrive
+const MAX_PREALLOCATION: usize = 16 * 1024 * 1024;
+
+/// Download `url` into memory
+///
+/// `on_progress` is called with the bytes received so far and the total size,
+/// `undefined` when the server doesn't send a `Content-Length`.
+pub async fn fetch_bytes(url: &str, on_progress: Option<&Function>) -> Result<Vec<u8>, JsValue> {
+    let response: Response = JsFuture::from(fetch_with_str(url)).await?.dyn_into()?;
+    if !response.ok() {
+        return Err(js_sys::Error::new(&format!(
+            "Failed to fetch {}: HTTP {}",
+            url,
+            response.status()
+        ))
+        .into());
+    }
+
+    let total = response
+        .headers()
+        .get("content-length")?
+        .and_then(|length| length.parse::<f64>().ok());
+    let Some(body) = response.body() else {
+        return Ok(Vec::new());
+    };
+    let reader: ReadableStreamDefaultReader = body.get_reader().dyn_into()?;
+
+    let capacity = total.map_or(0, |total| (total as usize).min(MAX_PREALLOCATION));
+    let mut data = Vec::with_capacity(capacity);
+    loop {
+        let chunk = JsFuture::from(reader.read()).await?;
+        if Reflect::get(&chunk, &"done".into())?.is_truthy() {
+            break;
+        }
+        let value: Uint8Array = Reflect::get(&chunk, &"value".into())?.dyn_into()?;
+        let offset = data.len();
+        data.resize(offset + value.length() as usize, 0);
+        value.copy_to(&mut data[offset..]);
+
+        if let Some(on_progress) = on_progress {
+            let total = total.map_or(JsValue::UNDEFINED, JsValue::from);
+            on_progress.call2(&JsValue::NULL, &JsValue::from(data.len() as f64), &total)?;
+        }
+    }
+    Ok(data)
+}
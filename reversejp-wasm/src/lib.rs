@@ -1,18 +1,53 @@
+mod batch;
+#[cfg(not(feature = "embedded"))]
+mod fetch;
+
 use js_sys::Error;
 use once_cell::sync::OnceCell;
+use once_cell::unsync;
 use reversejp::ReverseJp;
+#[cfg(feature = "embedded")]
 use reversejp::get_landslide_data;
 use wasm_bindgen::prelude::*;
 
+use batch::{RegionIndices, RegionTable};
+
 static GLOBAL_REVERSE_JP: OnceCell<ReverseJp> = OnceCell::new();
+static GLOBAL_REGION_TABLE: OnceCell<RegionTable> = OnceCell::new();
 
+fn to_js_error(err: impl std::fmt::Display) -> JsValue {
+    JsValue::from(Error::new(&err.to_string()))
+}
+
+#[cfg(feature = "embedded")]
 fn get_instance() -> Result<&'static ReverseJp, JsValue> {
-    GLOBAL_REVERSE_JP.get_or_try_init(|| {
-        ReverseJp::with_embedded_data().map_err(|err| JsValue::from(Error::new(&err.to_string())))
-    })
+    GLOBAL_REVERSE_JP.get_or_try_init(|| ReverseJp::with_embedded_data().map_err(to_js_error))
+}
+
+// Without embedded data, the instance only exists once a dataset has been loaded
+#[cfg(not(feature = "embedded"))]
+fn get_instance() -> Result<&'static ReverseJp, JsValue> {
+    GLOBAL_REVERSE_JP
+        .get()
+        .ok_or_else(|| to_js_error("No data loaded, call `initialize(url)` first"))
+}
+
+fn get_region_table() -> Result<&'static RegionTable, JsValue> {
+    let reverse_jp = get_instance()?;
+    Ok(GLOBAL_REGION_TABLE.get_or_init(|| RegionTable::new(reverse_jp)))
+}
+
+fn properties_to_js(
+    reverse_jp: &ReverseJp,
+    longitude: f64,
+    latitude: f64,
+) -> Result<JsValue, JsValue> {
+    let properties = reverse_jp.find_properties(longitude, latitude);
+    serde_wasm_bindgen::to_value(&properties).map_err(to_js_error)
 }
 
 /// Initialize the WebAssembly module and preload the embedded geospatial data.
+#[cfg(feature = "embedded")]
 #[wasm_bindgen]
 pub fn initialize() -> Result<(), JsValue> {
     console_error_panic_hook::set_once();
@@ -20,21 +55,194 @@ pub fn initialize() -> Result<(), JsValue> {
     Ok(())
 }
 
+/// Initialize the WebAssembly module with a dataset downloaded from `url`.
+///
+/// The dataset is GeoJSON or a ZIP archive of GeoJSON files, like the
+/// `reversejp-data.zip` shipped with the slim build. `on_progress` is called
+/// with `(loaded, total)` bytes as the download proceeds, `total` being
+/// `undefined` when the size is unknown.
+#[cfg(not(feature = "embedded"))]
+#[wasm_bindgen]
+pub async fn initialize(url: String, on_progress: Option<js_sys::Function>) -> Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    if GLOBAL_REVERSE_JP.get().is_some() {
+        return Ok(());
+    }
+    let data = fetch::fetch_bytes(&url, on_progress.as_ref()).await?;
+    initialize_from_bytes(&data)
+}
+
+/// Initialize the WebAssembly module with a dataset already in memory, e.g. read from IndexedDB.
+#[cfg(not(feature = "embedded"))]
+#[wasm_bindgen]
+pub fn initialize_from_bytes(data: &[u8]) -> Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    GLOBAL_REVERSE_JP.get_or_try_init(|| {
+        let mut reverse_jp = ReverseJp::new();
+        reverse_jp.load_bytes(data).map_err(to_js_error)?;
+        Ok::<_, JsValue>(reverse_jp)
+    })?;
+    Ok(())
+}
+
 /// Perform a reverse geocoding lookup for the given longitude and latitude.
 #[wasm_bindgen]
 pub fn find_properties(longitude: f64, latitude: f64) -> Result<JsValue, JsValue> {
-    let reverse_jp = get_instance()?;
-    let properties = reverse_jp.find_properties(longitude, latitude);
+    properties_to_js(get_instance()?, longitude, latitude)
+}
+
+// The GeoJSON geometry of a region, simplified when a tolerance is given
+fn geometry_to_js(
+    reverse_jp: &ReverseJp,
+    code: &str,
+    tolerance: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let Some(geometry) = reverse_jp.geometry(code) else {
+        return Ok(JsValue::UNDEFINED);
+    };
+    let geometry = match tolerance {
+        Some(tolerance) => geometry.simplify(tolerance),
+        None => geometry,
+    };
+    serde_wasm_bindgen::to_value(&geometry).map_err(to_js_error)
+}
+
+/// The GeoJSON `MultiPolygon` of a region, e.g. to highlight a code returned by `find_properties`.
+///
+/// `tolerance`, in degrees, simplifies the boundary; `undefined` when the code is unknown.
+#[wasm_bindgen]
+pub fn geometry(code: &str, tolerance: Option<f64>) -> Result<JsValue, JsValue> {
+    geometry_to_js(get_instance()?, code, tolerance)
+}
+
+/// Simplification tolerance, in degrees, matching one pixel at the given map zoom level.
+#[wasm_bindgen]
+pub fn tolerance_for_zoom(zoom: u8) -> f64 {
+    reversejp::simplify::tolerance_for_zoom(zoom)
+}
 
-    serde_wasm_bindgen::to_value(&properties)
-        .map_err(|err| JsValue::from(Error::new(&err.to_string())))
+/// All regions of the loaded data, the lookup table of `find_indices`.
+#[wasm_bindgen]
+pub fn regions() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&get_region_table()?.properties).map_err(to_js_error)
 }
 
+/// Look up many points at once, given as a `Float64Array` of interleaved longitude/latitude.
+///
+/// Returns the regions of each point as indices into the array returned by `regions()`.
+#[wasm_bindgen]
+pub fn find_indices(coordinates: &[f64]) -> Result<RegionIndices, JsValue> {
+    batch::find_indices(get_instance()?, coordinates)
+        .map(RegionIndices::from)
+        .map_err(to_js_error)
+}
+
+#[cfg(feature = "embedded")]
 #[wasm_bindgen]
 pub fn get_landslide_data_wasm(idx: usize) -> Result<JsValue, JsValue> {
     let result = get_landslide_data(idx);
     match result {
         Ok(data) => Ok(JsValue::from_str(&data)),
-        Err(err) => Err(JsValue::from(Error::new(&err.to_string()))),
+        Err(err) => Err(to_js_error(err)),
+    }
+}
+
+/// A reverse geocoder with its own regions.
+///
+/// Unlike the module-level functions, the data doesn't have to come from the
+/// `.wasm` itself: pages can fetch GeoJSON or the zipped `*.json.zip` files
+/// separately, e.g. to cache them with a service worker, and load them here.
+#[wasm_bindgen(js_name = ReverseJp)]
+pub struct WasmReverseJp {
+    inner: ReverseJp,
+    // Built on first batch lookup, reset whenever regions are loaded
+    table: unsync::OnceCell<RegionTable>,
+}
+
+#[wasm_bindgen(js_class = ReverseJp)]
+impl WasmReverseJp {
+    /// Create an instance with the embedded data, or an empty one when `embedded` is `false`.
+    ///
+    /// The slim build has no embedded data, and creates empty instances by default.
+    #[wasm_bindgen(constructor)]
+    pub fn new(embedded: Option<bool>) -> Result<WasmReverseJp, JsValue> {
+        console_error_panic_hook::set_once();
+        let inner = if embedded.unwrap_or(cfg!(feature = "embedded")) {
+            Self::embedded_data()?
+        } else {
+            ReverseJp::new()
+        };
+        Ok(Self {
+            inner,
+            table: unsync::OnceCell::new(),
+        })
+    }
+
+    /// Create an instance from a GeoJSON `FeatureCollection` string.
+    pub fn from_geojson(geojson: &str, embedded: Option<bool>) -> Result<WasmReverseJp, JsValue> {
+        let mut reverse_jp = Self::new(Some(embedded.unwrap_or(false)))?;
+        reverse_jp.load_geojson(geojson)?;
+        Ok(reverse_jp)
+    }
+
+    /// Create an instance from GeoJSON bytes or a ZIP archive of GeoJSON files.
+    pub fn from_bytes(data: &[u8], embedded: Option<bool>) -> Result<WasmReverseJp, JsValue> {
+        let mut reverse_jp = Self::new(Some(embedded.unwrap_or(false)))?;
+        reverse_jp.load_bytes(data)?;
+        Ok(reverse_jp)
+    }
+
+    /// Add the regions of a GeoJSON `FeatureCollection` string.
+    pub fn load_geojson(&mut self, geojson: &str) -> Result<(), JsValue> {
+        self.table = unsync::OnceCell::new();
+        self.inner.load_geojson(geojson).map_err(to_js_error)
+    }
+
+    /// Add the regions of a `Uint8Array` holding GeoJSON or a ZIP archive of GeoJSON files.
+    pub fn load_bytes(&mut self, data: &[u8]) -> Result<(), JsValue> {
+        self.table = unsync::OnceCell::new();
+        self.inner.load_bytes(data).map_err(to_js_error)
+    }
+
+    /// Perform a reverse geocoding lookup for the given longitude and latitude.
+    pub fn find_properties(&self, longitude: f64, latitude: f64) -> Result<JsValue, JsValue> {
+        properties_to_js(&self.inner, longitude, latitude)
+    }
+
+    /// The GeoJSON `MultiPolygon` of a region, optionally simplified with `tolerance` in degrees.
+    pub fn geometry(&self, code: &str, tolerance: Option<f64>) -> Result<JsValue, JsValue> {
+        geometry_to_js(&self.inner, code, tolerance)
+    }
+
+    /// All loaded regions, the lookup table of `find_indices`.
+    pub fn regions(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.region_table().properties).map_err(to_js_error)
+    }
+
+    /// Look up many points at once, given as a `Float64Array` of interleaved longitude/latitude.
+    ///
+    /// Returns the regions of each point as indices into the array returned by `regions()`.
+    pub fn find_indices(&self, coordinates: &[f64]) -> Result<RegionIndices, JsValue> {
+        batch::find_indices(&self.inner, coordinates)
+            .map(RegionIndices::from)
+            .map_err(to_js_error)
+    }
+}
+
+impl WasmReverseJp {
+    #[cfg(feature = "embedded")]
+    fn embedded_data() -> Result<ReverseJp, JsValue> {
+        ReverseJp::with_embedded_data().map_err(to_js_error)
+    }
+
+    #[cfg(not(feature = "embedded"))]
+    fn embedded_data() -> Result<ReverseJp, JsValue> {
+        Err(to_js_error(
+            "This build has no embedded data, load a dataset instead",
+        ))
+    }
+
+    fn region_table(&self) -> &RegionTable {
+        self.table.get_or_init(|| RegionTable::new(&self.inner))
     }
 }
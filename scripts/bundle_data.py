@@ -0,0 +1,21 @@
+"""Bundle the lookup regions into one ZIP archive for the slim WASM build.
+
+Usage: python3 scripts/bundle_data.py OUTPUT
+"""
+
+import sys
+import zipfile
+
+DATA_DIR = "reversejp-rust/data"
+NAMES = ["class10s"] + [f"landslides_{idx}" for idx in range(0, 10)]
+
+
+def bundle(output):
+    with zipfile.ZipFile(output, "w", zipfile.ZIP_DEFLATED) as out:
+        for name in NAMES:
+            with zipfile.ZipFile(f"{DATA_DIR}/{name}.json.zip") as zf:
+                out.writestr(f"{name}.json", zf.read(f"{name}.json"))
+
+
+if __name__ == "__main__":
+    bundle(sys.argv[1])